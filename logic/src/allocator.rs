@@ -0,0 +1,99 @@
+/**
+### Priority allocation of shared supply power
+* `budget`: total duty of all zones, 1.0 is one zone at full power
+* Lower zone index is served first, rest gets what is left
+*/
+pub fn allocate_priority<const N: usize>(demand: [f32; N], budget: f32) -> [f32; N] {
+    let demand = demand.map(|x| x.clamp(0.0, 1.0));
+    if demand.iter().sum::<f32>() <= budget {
+        return demand;
+    }
+
+    let mut granted = [0.0; N];
+    let mut remaining = budget;
+    for (granted, demand) in granted.iter_mut().zip(demand) {
+        *granted = demand.min(remaining);
+        remaining -= *granted;
+    }
+
+    granted
+}
+
+/**
+### Fair share allocation of shared supply power
+* `budget`: total duty of all zones, 1.0 is one zone at full power
+* Equal share each, share unused by a zone is split among the others
+*/
+pub fn allocate_fair_share<const N: usize>(demand: [f32; N], budget: f32) -> [f32; N] {
+    let demand = demand.map(|x| x.clamp(0.0, 1.0));
+    if demand.iter().sum::<f32>() <= budget {
+        return demand;
+    }
+
+    //water-filling, satisfied zones return unused share
+    let mut granted = [0.0; N];
+    let mut remaining = budget;
+    let mut open = [true; N];
+    loop {
+        let count = open.iter().filter(|x| **x).count();
+        if count == 0 {
+            break;
+        }
+        let share = remaining / count as f32;
+
+        let mut satisfied = false;
+        for ((granted, open), demand) in granted.iter_mut().zip(open.iter_mut()).zip(demand) {
+            if *open && demand <= share {
+                *granted = demand;
+                remaining -= demand;
+                *open = false;
+                satisfied = true;
+            }
+        }
+        if !satisfied {
+            for (granted, open) in granted.iter_mut().zip(open) {
+                if open {
+                    *granted = share;
+                }
+            }
+            break;
+        }
+    }
+
+    granted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn demand_within_budget_is_granted() {
+        assert_eq!(allocate_priority([0.5, 0.5], 1.0), [0.5, 0.5]);
+        assert_eq!(allocate_fair_share([0.5, 0.5], 1.0), [0.5, 0.5]);
+        assert_eq!(allocate_priority([1.5, -0.5], 2.0), [1.0, 0.0]);
+    }
+
+    #[test]
+    fn priority_serves_lower_index_first() {
+        assert_eq!(
+            allocate_priority([0.75, 0.75, 0.75], 1.0),
+            [0.75, 0.25, 0.0]
+        );
+        assert_eq!(allocate_priority([0.25, 0.5, 0.5], 1.0), [0.25, 0.5, 0.25]);
+    }
+
+    #[test]
+    fn fair_share_splits_budget() {
+        assert_eq!(allocate_fair_share([1.0, 1.0], 1.0), [0.5, 0.5]);
+    }
+
+    #[test]
+    fn fair_share_returns_unused_share() {
+        let granted = allocate_fair_share([0.1, 1.0, 1.0], 1.0);
+        assert_eq!(granted[0], 0.1);
+        assert!((granted[1] - 0.45).abs() < 1e-6);
+        assert!((granted[2] - 0.45).abs() < 1e-6);
+        assert!(granted.iter().sum::<f32>() <= 1.0 + 1e-6);
+    }
+}
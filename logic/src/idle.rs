@@ -0,0 +1,92 @@
+const IDLE_WARN_TIME: f32 = 60.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IdleEventEnum {
+    Warn,
+    Off,
+}
+
+/**
+### Inactivity timer for unattended static holds
+* Counts only while a hold is active, reset by button presses and heater commands
+* Warns `IDLE_WARN_TIME` before timeout, each event is returned once
+* Timeout in minutes, 0 disables
+*/
+pub struct IdleTimer {
+    timeout: f32,
+    elapsed: f32,
+    warned: bool,
+    off: bool,
+}
+
+impl IdleTimer {
+    pub fn new(timeout: u16) -> Self {
+        Self {
+            timeout: timeout as f32 * 60.0,
+            elapsed: 0.0,
+            warned: false,
+            off: false,
+        }
+    }
+
+    pub fn set_timeout(&mut self, timeout: u16) {
+        self.timeout = timeout as f32 * 60.0;
+        self.activity();
+    }
+
+    pub fn activity(&mut self) {
+        self.elapsed = 0.0;
+        self.warned = false;
+        self.off = false;
+    }
+
+    pub fn update(&mut self, dt: f32, holding: bool) -> Option<IdleEventEnum> {
+        if !holding || self.timeout <= 0.0 {
+            self.activity();
+            return None;
+        }
+
+        self.elapsed += dt;
+        if self.elapsed >= self.timeout && !self.off {
+            self.off = true;
+            Some(IdleEventEnum::Off)
+        } else if self.elapsed >= self.timeout - IDLE_WARN_TIME && !self.warned {
+            self.warned = true;
+            Some(IdleEventEnum::Warn)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn warns_then_switches_off_once() {
+        let mut idle = IdleTimer::new(2);
+        assert_eq!(idle.update(59.0, true), None);
+        assert_eq!(idle.update(1.0, true), Some(IdleEventEnum::Warn));
+        assert_eq!(idle.update(30.0, true), None);
+        assert_eq!(idle.update(30.0, true), Some(IdleEventEnum::Off));
+        assert_eq!(idle.update(30.0, true), None);
+    }
+
+    #[test]
+    fn activity_restarts_timeout() {
+        let mut idle = IdleTimer::new(2);
+        assert_eq!(idle.update(100.0, true), Some(IdleEventEnum::Warn));
+        idle.activity();
+        assert_eq!(idle.update(59.0, true), None);
+        //leaving the hold restarts it too
+        assert_eq!(idle.update(1.0, false), None);
+        assert_eq!(idle.update(59.0, true), None);
+    }
+
+    #[test]
+    fn zero_timeout_disables() {
+        let mut idle = IdleTimer::new(0);
+        assert_eq!(idle.update(1000.0, true), None);
+    }
+}
@@ -0,0 +1,119 @@
+const INTERLOCK_CLOSE_TIME: f32 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InterlockEdgeEnum {
+    Opened,
+    Closed,
+}
+
+/**
+### Debounce of safety interlock inputs
+* Opening is seen on the next step, closing must be stable for `INTERLOCK_CLOSE_TIME`
+* Disabled inputs are ignored
+*/
+pub struct InterlockFilter<const N: usize> {
+    open: [bool; N],
+    closed_time: [f32; N],
+}
+
+impl<const N: usize> InterlockFilter<N> {
+    /**
+    ### New filter
+    * Inputs open at boot count as open without edge
+    */
+    pub fn new(open: [bool; N]) -> Self {
+        Self {
+            open,
+            closed_time: [0.0; N],
+        }
+    }
+
+    pub fn disable(&mut self, index: usize) {
+        if let Some(x) = self.open.get_mut(index) {
+            *x = false;
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open.iter().any(|x| *x)
+    }
+
+    pub fn open(&self) -> [bool; N] {
+        self.open
+    }
+
+    /**
+    ### Filters one sample of inputs
+    * `high`: input reads open, `enabled`: input is used
+    * Returns state change of each input in this step
+    */
+    pub fn update(
+        &mut self,
+        high: [bool; N],
+        enabled: [bool; N],
+        dt: f32,
+    ) -> [Option<InterlockEdgeEnum>; N] {
+        let mut edges = [None; N];
+        for (i, edge) in edges.iter_mut().enumerate() {
+            if !enabled[i] {
+                continue;
+            }
+
+            if high[i] {
+                self.closed_time[i] = 0.0;
+                if !self.open[i] {
+                    self.open[i] = true;
+                    *edge = Some(InterlockEdgeEnum::Opened);
+                }
+            } else if self.open[i] {
+                self.closed_time[i] += dt;
+                if self.closed_time[i] >= INTERLOCK_CLOSE_TIME {
+                    self.open[i] = false;
+                    *edge = Some(InterlockEdgeEnum::Closed);
+                }
+            }
+        }
+
+        edges
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn opens_on_first_sample() {
+        let mut filter = InterlockFilter::new([false; 2]);
+        assert_eq!(
+            filter.update([true, false], [true; 2], 0.1),
+            [Some(InterlockEdgeEnum::Opened), None]
+        );
+        assert!(filter.is_open());
+        assert_eq!(filter.update([true, false], [true; 2], 0.1), [None, None]);
+    }
+
+    #[test]
+    fn closes_after_stable_time() {
+        let mut filter = InterlockFilter::new([true]);
+        assert_eq!(filter.update([false], [true], 0.3), [None]);
+        //bounce restarts close time
+        assert_eq!(filter.update([true], [true], 0.1), [None]);
+        assert_eq!(filter.update([false], [true], 0.3), [None]);
+        assert_eq!(
+            filter.update([false], [true], 0.2),
+            [Some(InterlockEdgeEnum::Closed)]
+        );
+        assert!(!filter.is_open());
+    }
+
+    #[test]
+    fn ignores_disabled_inputs() {
+        let mut filter = InterlockFilter::new([false; 2]);
+        assert_eq!(filter.update([true; 2], [false, true], 0.1)[0], None);
+        assert_eq!(filter.open(), [false, true]);
+
+        filter.disable(1);
+        assert!(!filter.is_open());
+    }
+}
@@ -0,0 +1,17 @@
+/*!
+### Hardware independent control logic
+* Used by firmware crate, no embassy-rp or bincode dependency
+* Persisted settings enums stay in firmware crate, logic takes plain values
+* Tests run on host, build target of `.cargo/config.toml` must be overridden:
+  `cargo test --manifest-path logic/Cargo.toml --target host-tuple`
+*/
+#![cfg_attr(not(test), no_std)]
+
+pub mod allocator;
+pub mod idle;
+pub mod interlock;
+pub mod output;
+pub mod pid;
+pub mod profile;
+pub mod source;
+pub mod thermocouple;
@@ -0,0 +1,223 @@
+use embassy_time::{Duration, Instant};
+
+/**
+### Time proportional output window
+* Demand 0..1 sets on time of a slow window, for zero-cross SSR
+* Demand is latched at window start, zero demand switches off immediately
+* Pulses shorter than `min_on` are skipped, gaps shorter than `min_off` are filled
+* Windows are aligned to a grid shifted by `phase`, staggered zones don't switch on together
+*/
+pub struct TimeProportional {
+    window: f32,
+    min_on: f32,
+    min_off: f32,
+    phase: f32,
+    window_begin: Option<Instant>,
+    on_time: f32,
+}
+
+impl TimeProportional {
+    pub fn new(window: f32, min_on: f32, min_off: f32) -> Self {
+        Self {
+            window,
+            min_on,
+            min_off,
+            phase: 0.0,
+            window_begin: None,
+            on_time: 0.0,
+        }
+    }
+
+    pub fn set_phase(&mut self, phase: f32) {
+        self.phase = phase;
+        self.reset();
+    }
+
+    pub fn set_settings(&mut self, window: f32, min_on: f32, min_off: f32) {
+        self.window = window;
+        self.min_on = min_on;
+        self.min_off = min_off;
+        self.reset();
+    }
+
+    pub fn reset(&mut self) {
+        self.window_begin = None;
+        self.on_time = 0.0;
+    }
+
+    pub fn compare_at(&mut self, demand: f32, top: u16, now: Instant) -> u16 {
+        let demand = demand.clamp(0.0, 1.0);
+        if demand <= 0.0 {
+            self.reset();
+            return 0;
+        }
+
+        let elapsed = match self.window_begin {
+            Some(begin) => (now - begin).as_millis() as f32 / 1000.0,
+            None => {
+                //first window starts on phase grid, demand is latched for its rest
+                let window_ms = ((self.window * 1000.0) as u64).max(1);
+                let phase_ms = (self.phase * window_ms as f32) as u64;
+                let offset_ms = (now.as_millis() + window_ms - phase_ms) % window_ms;
+                self.window_begin = Some(
+                    now.checked_sub(Duration::from_millis(offset_ms))
+                        .unwrap_or(now),
+                );
+                self.on_time = self.latch(demand);
+                offset_ms as f32 / 1000.0
+            }
+        };
+        let elapsed = if elapsed >= self.window {
+            self.window_begin = Some(now);
+            self.on_time = self.latch(demand);
+            0.0
+        } else {
+            elapsed
+        };
+
+        if elapsed < self.on_time {
+            top
+        } else {
+            0
+        }
+    }
+
+    fn latch(&self, demand: f32) -> f32 {
+        let on_time = demand * self.window;
+        if on_time < self.min_on {
+            0.0
+        } else if self.window - on_time < self.min_off {
+            self.window
+        } else {
+            on_time
+        }
+    }
+}
+
+/**
+### Output power limit
+* Applied to PID, bang-bang and profile override demand before output stage
+* Identification runs only get `max_duty`, they don't start when it is below their test duty
+* `max_duty` caps demand, `max_duty_soak` replaces it during soak stages
+* Soft start ramps cap from 0 over `soft_start` seconds after boot and on each new run
+* Faults reset the board, so a fault clear starts from boot
+*/
+pub struct PowerLimit {
+    max_duty: f32,
+    max_duty_soak: f32,
+    soft_start: f32,
+    elapsed: f32,
+}
+
+impl PowerLimit {
+    pub fn new(max_duty: f32, max_duty_soak: f32, soft_start: f32) -> Self {
+        Self {
+            max_duty,
+            max_duty_soak,
+            soft_start,
+            elapsed: 0.0,
+        }
+    }
+
+    pub fn set_settings(&mut self, max_duty: f32, max_duty_soak: f32, soft_start: f32) {
+        self.max_duty = max_duty;
+        self.max_duty_soak = max_duty_soak;
+        self.soft_start = soft_start;
+    }
+
+    pub fn restart(&mut self) {
+        self.elapsed = 0.0;
+    }
+
+    pub fn max_duty(&self) -> f32 {
+        self.max_duty
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        self.elapsed = (self.elapsed + dt).min(self.soft_start);
+    }
+
+    pub fn limit(&self, demand: f32, soak: bool) -> f32 {
+        let max_duty = if soak {
+            self.max_duty_soak.min(self.max_duty)
+        } else {
+            self.max_duty
+        };
+        let ramp = if self.soft_start > 0.0 {
+            (self.elapsed / self.soft_start).min(1.0)
+        } else {
+            1.0
+        };
+
+        demand.clamp(0.0, max_duty * ramp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(ms: u64) -> Instant {
+        Instant::from_millis(ms)
+    }
+
+    #[test]
+    fn window_latches_demand() {
+        let mut output = TimeProportional::new(1.0, 0.0, 0.0);
+        assert_eq!(output.compare_at(0.5, 100, at(10_000)), 100);
+        //demand change is ignored until next window
+        assert_eq!(output.compare_at(1.0, 100, at(10_600)), 0);
+        assert_eq!(output.compare_at(1.0, 100, at(11_000)), 100);
+        assert_eq!(output.compare_at(1.0, 100, at(11_900)), 100);
+    }
+
+    #[test]
+    fn zero_demand_switches_off_immediately() {
+        let mut output = TimeProportional::new(1.0, 0.0, 0.0);
+        assert_eq!(output.compare_at(1.0, 100, at(10_000)), 100);
+        assert_eq!(output.compare_at(0.0, 100, at(10_100)), 0);
+    }
+
+    #[test]
+    fn window_skips_short_pulses_and_gaps() {
+        let mut output = TimeProportional::new(1.0, 0.1, 0.1);
+        assert_eq!(output.compare_at(0.05, 100, at(10_000)), 0);
+        output.reset();
+        assert_eq!(output.compare_at(0.95, 100, at(10_000)), 100);
+        assert_eq!(output.compare_at(0.95, 100, at(10_970)), 100);
+    }
+
+    #[test]
+    fn window_starts_on_phase_grid() {
+        let mut output = TimeProportional::new(1.0, 0.0, 0.0);
+        output.set_phase(0.5);
+        //grid starts at 9.5 s, half window is already over
+        assert_eq!(output.compare_at(0.5, 100, at(10_000)), 0);
+        assert_eq!(output.compare_at(0.5, 100, at(10_500)), 100);
+        assert_eq!(output.compare_at(0.5, 100, at(11_000)), 0);
+    }
+
+    #[test]
+    fn power_limit_caps_demand() {
+        let limit = PowerLimit::new(0.8, 0.5, 0.0);
+        assert_eq!(limit.limit(1.0, false), 0.8);
+        assert_eq!(limit.limit(1.0, true), 0.5);
+        assert_eq!(limit.limit(0.3, true), 0.3);
+        assert_eq!(limit.limit(-1.0, false), 0.0);
+
+        let limit = PowerLimit::new(0.4, 0.5, 0.0);
+        assert_eq!(limit.limit(1.0, true), 0.4);
+    }
+
+    #[test]
+    fn power_limit_soft_starts() {
+        let mut limit = PowerLimit::new(1.0, 1.0, 10.0);
+        assert_eq!(limit.limit(1.0, false), 0.0);
+        limit.update(5.0);
+        assert_eq!(limit.limit(1.0, false), 0.5);
+        limit.update(10.0);
+        assert_eq!(limit.limit(1.0, false), 1.0);
+        limit.restart();
+        assert_eq!(limit.limit(1.0, false), 0.0);
+    }
+}
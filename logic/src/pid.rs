@@ -0,0 +1,126 @@
+use core::time::Duration;
+
+/**
+### PID controller
+* Integral is accumulated in output units (`i * error * dt`), gain changes don't step the output
+* Integral is clamped to optional limits
+* Derivative on measurement, setpoint steps don't kick the output
+* Derivative is skipped on first update after reset
+*/
+pub struct Controller {
+    target: f32,
+    p: f32,
+    i: f32,
+    d: f32,
+    integral: f32,
+    integral_min: Option<f32>,
+    integral_max: Option<f32>,
+    last_value: Option<f32>,
+}
+
+impl Controller {
+    pub fn new(target: f32, p: f32, i: f32, d: f32) -> Self {
+        Self {
+            target,
+            p,
+            i,
+            d,
+            integral: 0.0,
+            integral_min: None,
+            integral_max: None,
+            last_value: None,
+        }
+    }
+
+    pub fn set_target(&mut self, target: f32) {
+        self.target = target;
+    }
+
+    pub fn set_proportional_gain(&mut self, p: f32) {
+        self.p = p;
+    }
+
+    pub fn set_integral_gain(&mut self, i: f32) {
+        self.i = i;
+    }
+
+    pub fn set_derivative_gain(&mut self, d: f32) {
+        self.d = d;
+    }
+
+    pub fn set_integral_limits(&mut self, min: Option<f32>, max: Option<f32>) {
+        self.integral_min = min;
+        self.integral_max = max;
+    }
+
+    pub fn reset(&mut self) {
+        self.integral = 0.0;
+        self.last_value = None;
+    }
+
+    pub fn update_elapsed(&mut self, current: f32, elapsed: Duration) -> f32 {
+        let dt = elapsed.as_secs_f32();
+        let error = self.target - current;
+
+        self.integral += self.i * error * dt;
+        if let Some(min) = self.integral_min {
+            self.integral = self.integral.max(min);
+        }
+        if let Some(max) = self.integral_max {
+            self.integral = self.integral.min(max);
+        }
+
+        let derivative = match self.last_value {
+            Some(last_value) if dt > 0.0 => (last_value - current) / dt,
+            _ => 0.0,
+        };
+        self.last_value = Some(current);
+
+        self.p * error + self.integral + self.d * derivative
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STEP: Duration = Duration::from_secs(1);
+
+    #[test]
+    fn proportional_and_integral() {
+        let mut pid = Controller::new(100.0, 2.0, 0.5, 0.0);
+        assert_eq!(pid.update_elapsed(90.0, STEP), 25.0);
+        assert_eq!(pid.update_elapsed(90.0, STEP), 30.0);
+    }
+
+    #[test]
+    fn integral_is_clamped() {
+        let mut pid = Controller::new(100.0, 0.0, 1.0, 0.0);
+        pid.set_integral_limits(Some(-5.0), Some(15.0));
+        assert_eq!(pid.update_elapsed(90.0, STEP), 10.0);
+        assert_eq!(pid.update_elapsed(90.0, STEP), 15.0);
+        assert_eq!(pid.update_elapsed(120.0, STEP), -5.0);
+    }
+
+    #[test]
+    fn integral_survives_gain_change() {
+        let mut pid = Controller::new(100.0, 0.0, 1.0, 0.0);
+        pid.update_elapsed(90.0, STEP);
+        pid.set_integral_gain(0.0);
+        assert_eq!(pid.update_elapsed(90.0, STEP), 10.0);
+    }
+
+    #[test]
+    fn derivative_on_measurement() {
+        let mut pid = Controller::new(100.0, 0.0, 0.0, 2.0);
+        //first update after reset has no derivative
+        assert_eq!(pid.update_elapsed(50.0, STEP), 0.0);
+        assert_eq!(pid.update_elapsed(53.0, STEP), -6.0);
+        //setpoint step doesn't kick
+        pid.set_target(200.0);
+        assert_eq!(pid.update_elapsed(53.0, STEP), 0.0);
+
+        pid.reset();
+        assert_eq!(pid.update_elapsed(60.0, STEP), 0.0);
+    }
+}
@@ -0,0 +1,60 @@
+//float math for no_std, shadowed by std in host tests
+#[allow(unused_imports)]
+use micromath::F32Ext;
+
+/**
+### Ramp target with lag lead
+* Ramps from `start + lead` to `end` as `progress` goes 0..1
+* Lead can't start the ramp above `end`, ramps ending below `start` hold `end`
+*/
+pub fn lead_ramp(start: u16, end: u16, lead: i16, progress: f32) -> u16 {
+    let lead = (lead as i32).min(end as i32 - start as i32);
+    let start = start as i32 + lead;
+    let temp_diff = end as i32 - start;
+    (start + (temp_diff as f32 * progress.clamp(0.0, 1.0)) as i32).max(0) as u16
+}
+
+/**
+### Predicted plate lag behind ramping target
+* Closed loop tuned by IMC behaves like `e^(-L*s) / (lambda*s + 1)`
+* On a ramp of `rate` °C/s it lags by `rate * (lambda + L)`, clamped to 0..`max`
+*/
+pub fn lag_lead(rate: f32, lambda: f32, dead_time: f32, max: f32) -> i16 {
+    let lead = rate * (lambda + dead_time);
+    lead.clamp(0.0, max).round() as i16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ramp_clamps_large_lead() {
+        assert_eq!(lead_ramp(220, 230, 50, 0.0), 230);
+        assert_eq!(lead_ramp(220, 230, 50, 0.5), 230);
+        assert_eq!(lead_ramp(220, 230, 50, 1.0), 230);
+    }
+
+    #[test]
+    fn ramp_applies_lead() {
+        assert_eq!(lead_ramp(220, 260, 30, 0.0), 250);
+        assert_eq!(lead_ramp(220, 260, 30, 0.5), 255);
+        assert_eq!(lead_ramp(220, 260, 30, 1.0), 260);
+        assert_eq!(lead_ramp(220, 260, 0, 0.5), 240);
+    }
+
+    #[test]
+    fn ramp_below_start() {
+        assert_eq!(lead_ramp(220, 200, 0, 0.0), 200);
+        assert_eq!(lead_ramp(220, 200, 20, 0.5), 200);
+    }
+
+    #[test]
+    fn lag_lead_is_clamped() {
+        //peak ramp 220..230 in 20 s, lambda 100 s, L 20 s
+        assert_eq!(lag_lead(0.5, 100.0, 20.0, 50.0), 50);
+        //peak ramp 220..260 in 20 s, lambda 10 s, L 5 s
+        assert_eq!(lag_lead(2.0, 10.0, 5.0, 50.0), 30);
+        assert_eq!(lag_lead(-1.0, 10.0, 5.0, 50.0), 0);
+    }
+}
@@ -0,0 +1,25 @@
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TemperatureReading {
+    pub temp: f32,
+    pub noise: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TemperatureSourceFault {
+    Adc,
+    Bus,
+    OpenCircuit,
+    ShortToGnd,
+    ShortToVcc,
+}
+
+/**
+### Temperature sensor read by `Heater`
+* `read` returns the temperature in °C and its noise (std-dev) in °C
+* Sensor faults are reported as errors, `Heater` decides how to handle them
+*/
+//single threaded executor, futures don't need to be Send
+#[allow(async_fn_in_trait)]
+pub trait TemperatureSource {
+    async fn read(&mut self) -> Result<TemperatureReading, TemperatureSourceFault>;
+}
@@ -0,0 +1,361 @@
+use embassy_time::{Duration, Instant};
+use embedded_hal_async::spi::SpiDevice;
+//float math for no_std, shadowed by std in host tests
+#[allow(unused_imports)]
+use micromath::F32Ext;
+
+use crate::source::{TemperatureReading, TemperatureSource, TemperatureSourceFault};
+
+const MAX31855_FAULT: u32 = 1 << 16;
+const MAX31855_SCV: u32 = 1 << 2;
+const MAX31855_SCG: u32 = 1 << 1;
+const MAX31855_OC: u32 = 1 << 0;
+const MAX6675_OC: u16 = 1 << 2;
+const RESOLUTION: f32 = 0.25;
+const NOISE_FILTER: f32 = 0.1;
+const MAX31855_READ_INTERVAL: Duration = Duration::from_millis(100);
+const MAX6675_READ_INTERVAL: Duration = Duration::from_millis(220);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ThermocoupleChip {
+    Max31855,
+    Max6675,
+}
+
+/**
+### MAX31855/MAX6675 K-type thermocouple converter
+* Read-only SPI device, one conversion per frame
+* Reads are rate limited to the conversion time, reading MAX6675 early restarts its conversion
+* Between reads the last result is returned
+* Noise is estimated from the spread of consecutive conversions
+*/
+pub struct Thermocouple<SPI> {
+    spi: SPI,
+    chip: ThermocoupleChip,
+    last_read: Option<(Instant, Result<f32, TemperatureSourceFault>)>,
+    last_temp: Option<f32>,
+    noise: f32,
+}
+
+impl<SPI: SpiDevice> Thermocouple<SPI> {
+    pub fn new(spi: SPI, chip: ThermocoupleChip) -> Self {
+        Self {
+            spi,
+            chip,
+            last_read: None,
+            last_temp: None,
+            noise: 0.0,
+        }
+    }
+
+    fn read_interval(&self) -> Duration {
+        match self.chip {
+            ThermocoupleChip::Max31855 => MAX31855_READ_INTERVAL,
+            ThermocoupleChip::Max6675 => MAX6675_READ_INTERVAL,
+        }
+    }
+
+    pub async fn read_temp(&mut self) -> Result<f32, TemperatureSourceFault> {
+        match self.chip {
+            ThermocoupleChip::Max31855 => {
+                let mut buf = [0u8; 4];
+                self.spi
+                    .read(&mut buf)
+                    .await
+                    .map_err(|_| TemperatureSourceFault::Bus)?;
+                decode_max31855(u32::from_be_bytes(buf))
+            }
+            ThermocoupleChip::Max6675 => {
+                let mut buf = [0u8; 2];
+                self.spi
+                    .read(&mut buf)
+                    .await
+                    .map_err(|_| TemperatureSourceFault::Bus)?;
+                decode_max6675(u16::from_be_bytes(buf))
+            }
+        }
+    }
+
+    async fn read_at(
+        &mut self,
+        now: Instant,
+    ) -> Result<TemperatureReading, TemperatureSourceFault> {
+        if let Some((read_at, result)) = self.last_read {
+            if now < read_at + self.read_interval() {
+                return result.map(|temp| TemperatureReading {
+                    temp,
+                    noise: self.noise,
+                });
+            }
+        }
+
+        let result = self.read_temp().await;
+        self.last_read = Some((now, result));
+        let temp = result?;
+
+        if let Some(last_temp) = self.last_temp {
+            self.noise += ((temp - last_temp).abs() - self.noise) * NOISE_FILTER;
+        }
+        self.last_temp = Some(temp);
+
+        Ok(TemperatureReading {
+            temp,
+            noise: self.noise,
+        })
+    }
+}
+
+impl<SPI: SpiDevice> TemperatureSource for Thermocouple<SPI> {
+    async fn read(&mut self) -> Result<TemperatureReading, TemperatureSourceFault> {
+        self.read_at(Instant::now()).await
+    }
+}
+
+/**
+### Decodes MAX31855 frame
+* D31..D18: thermocouple temperature, signed, 0.25 °C
+* D16: fault, D2: short to VCC, D1: short to GND, D0: open circuit
+*/
+pub fn decode_max31855(raw: u32) -> Result<f32, TemperatureSourceFault> {
+    if raw & MAX31855_FAULT != 0 {
+        return Err(if raw & MAX31855_OC != 0 {
+            TemperatureSourceFault::OpenCircuit
+        } else if raw & MAX31855_SCG != 0 {
+            TemperatureSourceFault::ShortToGnd
+        } else if raw & MAX31855_SCV != 0 {
+            TemperatureSourceFault::ShortToVcc
+        } else {
+            TemperatureSourceFault::Bus
+        });
+    }
+
+    let val = (raw as i32) >> 18;
+    Ok(val as f32 * RESOLUTION)
+}
+
+/**
+### Decodes MAX6675 frame
+* D14..D3: thermocouple temperature, unsigned, 0.25 °C
+* D2: open circuit
+*/
+pub fn decode_max6675(raw: u16) -> Result<f32, TemperatureSourceFault> {
+    if raw & MAX6675_OC != 0 {
+        return Err(TemperatureSourceFault::OpenCircuit);
+    }
+
+    let val = (raw >> 3) & 0x0FFF;
+    Ok(val as f32 * RESOLUTION)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embassy_futures::block_on;
+    use embedded_hal_1::spi::{ErrorKind, ErrorType, Operation};
+
+    struct MockSpi {
+        frame: [u8; 4],
+        fail: bool,
+        reads: usize,
+    }
+
+    impl MockSpi {
+        fn new(frame: [u8; 4]) -> Self {
+            Self {
+                frame,
+                fail: false,
+                reads: 0,
+            }
+        }
+    }
+
+    impl ErrorType for MockSpi {
+        type Error = ErrorKind;
+    }
+
+    impl SpiDevice for MockSpi {
+        async fn transaction(
+            &mut self,
+            operations: &mut [Operation<'_, u8>],
+        ) -> Result<(), Self::Error> {
+            if self.fail {
+                return Err(ErrorKind::Other);
+            }
+            for op in operations {
+                if let Operation::Read(buf) = op {
+                    let len = buf.len();
+                    buf.copy_from_slice(&self.frame[..len]);
+                    self.reads += 1;
+                }
+            }
+            Ok(())
+        }
+    }
+
+    fn max31855_frame(temp: f32, faults: u32) -> [u8; 4] {
+        let val = ((temp / RESOLUTION) as i32 as u32) << 18;
+        (val | faults).to_be_bytes()
+    }
+
+    fn max6675_frame(temp: f32, faults: u16) -> [u8; 4] {
+        let val = ((temp / RESOLUTION) as u16) << 3;
+        let [hi, lo] = (val | faults).to_be_bytes();
+        [hi, lo, 0, 0]
+    }
+
+    #[test]
+    fn max31855_decodes_temperature() {
+        assert_eq!(decode_max31855(0x0640_0000), Ok(100.0));
+        assert_eq!(decode_max31855(0x0064_0000), Ok(6.25));
+        assert_eq!(decode_max31855(0xFFFC_0000), Ok(-0.25));
+        assert_eq!(decode_max31855(0xF060_0000), Ok(-250.0));
+    }
+
+    #[test]
+    fn max31855_decodes_faults() {
+        assert_eq!(
+            decode_max31855(MAX31855_FAULT | MAX31855_OC),
+            Err(TemperatureSourceFault::OpenCircuit)
+        );
+        assert_eq!(
+            decode_max31855(MAX31855_FAULT | MAX31855_SCG),
+            Err(TemperatureSourceFault::ShortToGnd)
+        );
+        assert_eq!(
+            decode_max31855(MAX31855_FAULT | MAX31855_SCV),
+            Err(TemperatureSourceFault::ShortToVcc)
+        );
+        assert_eq!(
+            decode_max31855(MAX31855_FAULT),
+            Err(TemperatureSourceFault::Bus)
+        );
+        //fault detail bits without D16 are not a fault
+        assert_eq!(decode_max31855(0x0640_0000 | MAX31855_OC), Ok(100.0));
+    }
+
+    #[test]
+    fn max6675_decodes_temperature() {
+        assert_eq!(decode_max6675(0x0C80), Ok(100.0));
+        assert_eq!(decode_max6675(0x7FF8), Ok(1023.75));
+        assert_eq!(decode_max6675(0x0000), Ok(0.0));
+    }
+
+    #[test]
+    fn max6675_decodes_open_thermocouple() {
+        assert_eq!(
+            decode_max6675(0x0C80 | MAX6675_OC),
+            Err(TemperatureSourceFault::OpenCircuit)
+        );
+    }
+
+    #[test]
+    fn driver_reads_max31855() {
+        let mut tc = Thermocouple::new(
+            MockSpi::new(max31855_frame(215.5, 0)),
+            ThermocoupleChip::Max31855,
+        );
+        let reading = block_on(tc.read_at(Instant::from_millis(0))).unwrap();
+        assert_eq!(reading.temp, 215.5);
+        assert_eq!(tc.spi.reads, 1);
+    }
+
+    #[test]
+    fn driver_reads_max6675() {
+        let mut tc = Thermocouple::new(
+            MockSpi::new(max6675_frame(42.75, 0)),
+            ThermocoupleChip::Max6675,
+        );
+        let reading = block_on(tc.read_at(Instant::from_millis(0))).unwrap();
+        assert_eq!(reading.temp, 42.75);
+        assert_eq!(tc.spi.reads, 1);
+    }
+
+    #[test]
+    fn driver_reports_faults() {
+        let mut tc = Thermocouple::new(
+            MockSpi::new(max31855_frame(0.0, MAX31855_FAULT | MAX31855_OC)),
+            ThermocoupleChip::Max31855,
+        );
+        assert_eq!(
+            block_on(tc.read_at(Instant::from_millis(0))).map(|r| r.temp),
+            Err(TemperatureSourceFault::OpenCircuit)
+        );
+
+        let mut tc = Thermocouple::new(
+            MockSpi::new(max31855_frame(0.0, MAX31855_FAULT | MAX31855_SCG)),
+            ThermocoupleChip::Max31855,
+        );
+        assert_eq!(
+            block_on(tc.read_at(Instant::from_millis(0))).map(|r| r.temp),
+            Err(TemperatureSourceFault::ShortToGnd)
+        );
+
+        let mut tc = Thermocouple::new(
+            MockSpi::new(max6675_frame(0.0, MAX6675_OC)),
+            ThermocoupleChip::Max6675,
+        );
+        assert_eq!(
+            block_on(tc.read_at(Instant::from_millis(0))).map(|r| r.temp),
+            Err(TemperatureSourceFault::OpenCircuit)
+        );
+
+        let mut spi = MockSpi::new(max31855_frame(25.0, 0));
+        spi.fail = true;
+        let mut tc = Thermocouple::new(spi, ThermocoupleChip::Max31855);
+        assert_eq!(
+            block_on(tc.read_at(Instant::from_millis(0))).map(|r| r.temp),
+            Err(TemperatureSourceFault::Bus)
+        );
+    }
+
+    #[test]
+    fn driver_rate_limits_max6675() {
+        let mut tc = Thermocouple::new(
+            MockSpi::new(max6675_frame(100.0, 0)),
+            ThermocoupleChip::Max6675,
+        );
+        block_on(tc.read_at(Instant::from_millis(0))).unwrap();
+
+        tc.spi.frame = max6675_frame(101.0, 0);
+        let reading = block_on(tc.read_at(Instant::from_millis(219))).unwrap();
+        assert_eq!(reading.temp, 100.0);
+        assert_eq!(tc.spi.reads, 1);
+
+        let reading = block_on(tc.read_at(Instant::from_millis(220))).unwrap();
+        assert_eq!(reading.temp, 101.0);
+        assert_eq!(tc.spi.reads, 2);
+    }
+
+    #[test]
+    fn driver_rate_limits_max31855() {
+        let mut tc = Thermocouple::new(
+            MockSpi::new(max31855_frame(100.0, 0)),
+            ThermocoupleChip::Max31855,
+        );
+        block_on(tc.read_at(Instant::from_millis(0))).unwrap();
+        block_on(tc.read_at(Instant::from_millis(99))).unwrap();
+        assert_eq!(tc.spi.reads, 1);
+        block_on(tc.read_at(Instant::from_millis(100))).unwrap();
+        assert_eq!(tc.spi.reads, 2);
+    }
+
+    #[test]
+    fn driver_caches_faults() {
+        let mut tc = Thermocouple::new(
+            MockSpi::new(max31855_frame(0.0, MAX31855_FAULT | MAX31855_OC)),
+            ThermocoupleChip::Max31855,
+        );
+        block_on(tc.read_at(Instant::from_millis(0))).unwrap_err();
+
+        tc.spi.frame = max31855_frame(25.0, 0);
+        assert_eq!(
+            block_on(tc.read_at(Instant::from_millis(50))).map(|r| r.temp),
+            Err(TemperatureSourceFault::OpenCircuit)
+        );
+        assert_eq!(
+            block_on(tc.read_at(Instant::from_millis(100))).map(|r| r.temp),
+            Ok(25.0)
+        );
+        assert_eq!(tc.spi.reads, 2);
+    }
+}
@@ -1,6 +1,7 @@
 MEMORY {
     BOOT2   : ORIGIN = 0x10000000, LENGTH = 0x100
-    FLASH   : ORIGIN = 0x10000100, LENGTH = 2048K - 0x100 - 4K
+    FLASH   : ORIGIN = 0x10000100, LENGTH = 2048K - 0x100 - 8K
+    STATS   : ORIGIN = 0x101FE000, LENGTH = 4K
     STORAGE : ORIGIN = 0x101FF000, LENGTH = 4K
 
     /* Pick one of the two options for RAM layout     */
@@ -0,0 +1,52 @@
+use bincode::{Decode, Encode};
+use reflow_plate_logic::allocator::{allocate_fair_share, allocate_priority};
+
+use crate::zone::ZONES;
+
+#[derive(Debug, Clone, Copy, PartialEq, Encode, Decode)]
+pub(crate) enum AllocPolicyEnum {
+    Priority,
+    FairShare,
+}
+
+/**
+### Shared supply power allocator
+* Sits between zone demands and outputs, index 0 is main zone
+* `budget`: total duty of all zones, 1.0 is one zone at full power
+* Priority: lower zone index is served first, rest gets what is left
+* FairShare: equal share each, share unused by a zone is split among the others
+* Zones are phase-staggered by `phase`, overlap of on-times is spread within budget
+*/
+pub(crate) struct PowerAllocator {
+    budget: f32,
+    policy: AllocPolicyEnum,
+}
+
+impl PowerAllocator {
+    pub fn new(budget: u16, policy: AllocPolicyEnum) -> Self {
+        Self {
+            budget: budget as f32 / 100.0,
+            policy,
+        }
+    }
+
+    pub fn set_settings(&mut self, budget: u16, policy: AllocPolicyEnum) {
+        self.budget = budget as f32 / 100.0;
+        self.policy = policy;
+    }
+
+    /**
+    ### Phase of zone output period
+    * Evenly spaced 0..1
+    */
+    pub fn phase(index: usize) -> f32 {
+        index as f32 / ZONES as f32
+    }
+
+    pub fn allocate(&self, demand: [f32; ZONES]) -> [f32; ZONES] {
+        match self.policy {
+            AllocPolicyEnum::Priority => allocate_priority(demand, self.budget),
+            AllocPolicyEnum::FairShare => allocate_fair_share(demand, self.budget),
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use core::f32::consts::PI;
+
+use bincode::{Decode, Encode};
+use micromath::F32Ext;
+
+pub(crate) const AUTOTUNE_CYCLES_MAX: usize = 12;
+const AUTOTUNE_SKIP_CYCLES: u8 = 1;
+const OUTLIER_LIMIT: f32 = 0.2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Encode, Decode)]
+pub(crate) enum TuningRuleEnum {
+    ZieglerNichols,
+    TyreusLuyben,
+    SomeOvershoot,
+    NoOvershoot,
+}
+
+impl TuningRuleEnum {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TuningRuleEnum::ZieglerNichols => "ZN",
+            TuningRuleEnum::TyreusLuyben => "TL",
+            TuningRuleEnum::SomeOvershoot => "some OS",
+            TuningRuleEnum::NoOvershoot => "no OS",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            TuningRuleEnum::ZieglerNichols => TuningRuleEnum::TyreusLuyben,
+            TuningRuleEnum::TyreusLuyben => TuningRuleEnum::SomeOvershoot,
+            TuningRuleEnum::SomeOvershoot => TuningRuleEnum::NoOvershoot,
+            TuningRuleEnum::NoOvershoot => TuningRuleEnum::ZieglerNichols,
+        }
+    }
+
+    /**
+    ### PID gains from ultimate gain and period
+    * Returns (kp, ki, kd)
+    */
+    pub fn gains(&self, ku: f32, tu: f32) -> (f32, f32, f32) {
+        let (kp_ku, ti_tu, td_tu) = match self {
+            TuningRuleEnum::ZieglerNichols => (0.6, 0.5, 0.125),
+            TuningRuleEnum::TyreusLuyben => (0.4545, 2.2, 0.1587),
+            TuningRuleEnum::SomeOvershoot => (0.33, 0.5, 0.33),
+            TuningRuleEnum::NoOvershoot => (0.2, 0.5, 0.33),
+        };
+
+        let kp = kp_ku * ku;
+        let ti = ti_tu * tu;
+        let td = td_tu * tu;
+
+        (kp, kp / ti, kp * td)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct RelayResult {
+    pub ku: f32,
+    pub tu: f32,
+    pub amplitude: f32,
+    pub quality: f32,
+}
+
+/**
+### Åström–Hägglund relay autotune
+* Relay output swings between 0 and `amplitude` duty
+* Switches at setpoint ± `hysteresis`
+* One cycle is measured between consecutive switches to heating
+*/
+pub(crate) struct RelayAutoTune {
+    amplitude: f32,
+    hysteresis: f32,
+    cycles: u8,
+    samples: [(f32, f32); AUTOTUNE_CYCLES_MAX],
+    count: usize,
+    skipped: u8,
+    cycle_start: Option<f32>,
+    cycle_max: f32,
+    cycle_min: f32,
+}
+
+impl RelayAutoTune {
+    pub fn new(amplitude: f32, hysteresis: f32, cycles: u8) -> Self {
+        Self {
+            amplitude,
+            hysteresis,
+            cycles,
+            samples: [(0.0, 0.0); AUTOTUNE_CYCLES_MAX],
+            count: 0,
+            skipped: 0,
+            cycle_start: None,
+            cycle_max: f32::MIN,
+            cycle_min: f32::MAX,
+        }
+    }
+
+    pub fn set_settings(&mut self, amplitude: f32, hysteresis: f32, cycles: u8) {
+        self.amplitude = amplitude;
+        self.hysteresis = hysteresis;
+        self.cycles = cycles;
+    }
+
+    pub fn reset(&mut self) {
+        self.count = 0;
+        self.skipped = 0;
+        self.cycle_start = None;
+        self.cycle_max = f32::MIN;
+        self.cycle_min = f32::MAX;
+    }
+
+    pub fn amplitude(&self) -> f32 {
+        self.amplitude
+    }
+
+    pub fn hysteresis(&self) -> f32 {
+        self.hysteresis
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn done(&self) -> bool {
+        self.count >= (self.cycles as usize).clamp(1, AUTOTUNE_CYCLES_MAX)
+    }
+
+    pub fn track(&mut self, temp: f32) {
+        self.cycle_max = self.cycle_max.max(temp);
+        self.cycle_min = self.cycle_min.min(temp);
+    }
+
+    /**
+    ### Relay switched to heating
+    * Closes current cycle, returns true if it was recorded
+    */
+    pub fn cycle(&mut self, time: f32) -> bool {
+        let mut recorded = false;
+        if let Some(start) = self.cycle_start {
+            if self.skipped < AUTOTUNE_SKIP_CYCLES {
+                self.skipped += 1;
+            } else if self.count < AUTOTUNE_CYCLES_MAX {
+                self.samples[self.count] = ((self.cycle_max - self.cycle_min) * 0.5, time - start);
+                self.count += 1;
+                recorded = true;
+            }
+        }
+
+        self.cycle_start = Some(time);
+        self.cycle_max = f32::MIN;
+        self.cycle_min = f32::MAX;
+        recorded
+    }
+
+    /**
+    ### Averages recorded cycles
+    * Cycles with amplitude or period farther than 20% from median are rejected
+    * Quality 0..1: share of accepted cycles, reduced by period spread
+    */
+    pub fn result(&self) -> Option<RelayResult> {
+        if self.count == 0 {
+            return None;
+        }
+
+        let samples = &self.samples[..self.count];
+        let median_amplitude = median(samples.iter().map(|x| x.0));
+        let median_period = median(samples.iter().map(|x| x.1));
+        let accepted = |a: f32, p: f32| {
+            (a - median_amplitude).abs() <= median_amplitude * OUTLIER_LIMIT
+                && (p - median_period).abs() <= median_period * OUTLIER_LIMIT
+        };
+
+        let mut amplitude = 0.0f32;
+        let mut period = 0.0f32;
+        let mut n = 0;
+        for &(a, p) in samples {
+            if accepted(a, p) {
+                amplitude += a;
+                period += p;
+                n += 1;
+            }
+        }
+        if n == 0 {
+            amplitude = median_amplitude;
+            period = median_period;
+        } else {
+            amplitude /= n as f32;
+            period /= n as f32;
+        }
+
+        let mut period_var = 0.0f32;
+        for &(a, p) in samples {
+            if accepted(a, p) {
+                period_var += (p - period) * (p - period);
+            }
+        }
+        let period_cv = if n == 0 {
+            1.0
+        } else {
+            (period_var / n as f32).sqrt() / period.max(0.01)
+        };
+        let quality = (n as f32 / self.count as f32) * (1.0 - period_cv).clamp(0.0, 1.0);
+
+        //relay swings 0..amplitude, d is half of it
+        let d = self.amplitude * 0.5;
+        let a = (amplitude * amplitude - self.hysteresis * self.hysteresis).max(0.01);
+
+        Some(RelayResult {
+            ku: 4.0 * d / (PI * a.sqrt()),
+            tu: period,
+            amplitude,
+            quality,
+        })
+    }
+}
+
+fn median(it: impl Iterator<Item = f32>) -> f32 {
+    let mut buf = [0.0f32; AUTOTUNE_CYCLES_MAX];
+    let mut n = 0;
+    for x in it.take(AUTOTUNE_CYCLES_MAX) {
+        buf[n] = x;
+        n += 1;
+    }
+    let buf = &mut buf[..n];
+    buf.sort_unstable_by(|a, b| a.total_cmp(b));
+    buf[n / 2]
+}
@@ -0,0 +1,57 @@
+/**
+### On/off control for non-PID mode
+* Switches on below `target - below`, off at `target + above`
+* State is held for at least `min_switch` seconds after each change
+* Zero target switches off immediately
+*/
+pub(crate) struct BangBang {
+    below: f32,
+    above: f32,
+    min_switch: f32,
+    on: bool,
+    since_switch: f32,
+}
+
+impl BangBang {
+    pub fn new(below: f32, above: f32, min_switch: f32) -> Self {
+        Self {
+            below,
+            above,
+            min_switch,
+            on: false,
+            since_switch: min_switch,
+        }
+    }
+
+    pub fn set_settings(&mut self, below: f32, above: f32, min_switch: f32) {
+        self.below = below;
+        self.above = above;
+        self.min_switch = min_switch;
+    }
+
+    pub fn reset(&mut self) {
+        self.on = false;
+        self.since_switch = self.min_switch;
+    }
+
+    pub fn update(&mut self, target: f32, temp: f32, dt: f32) -> bool {
+        self.since_switch += dt;
+
+        if target <= 0.0 {
+            self.on = false;
+            return false;
+        }
+
+        let on = if self.on {
+            temp < target + self.above
+        } else {
+            temp < target - self.below
+        };
+        if on != self.on && self.since_switch >= self.min_switch {
+            self.on = on;
+            self.since_switch = 0.0;
+        }
+
+        self.on
+    }
+}
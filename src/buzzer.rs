@@ -0,0 +1,109 @@
+use embassy_rp::peripherals::{PIN_16, PWM_SLICE0};
+use embassy_rp::pwm::{self, Pwm};
+use embassy_time::Timer;
+use fixed::traits::ToFixed;
+
+use crate::channels;
+use crate::tools::SyncStateChannelReceiver;
+
+const BUZZER_CLOCK: u32 = 125_000_000 / 64;
+const FAULT_FREQ: u16 = 2000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum SyncBuzzerStateEnum {
+    Enabled(bool),
+    Start,
+    PlaceBoard,
+    PeakReached,
+    RemoveBoard,
+    Complete,
+    IdleWarning,
+}
+
+//(frequency Hz, on ms, off ms)
+type Pattern = &'static [(u16, u16, u16)];
+
+const PATTERN_START: Pattern = &[(2000, 80, 40), (2500, 80, 0)];
+const PATTERN_PLACE_BOARD: Pattern = &[(2500, 300, 200), (2500, 300, 200), (2500, 300, 0)];
+const PATTERN_PEAK_REACHED: Pattern = &[(3000, 150, 80), (3000, 150, 0)];
+const PATTERN_REMOVE_BOARD: Pattern = &[
+    (3000, 100, 60),
+    (2500, 100, 60),
+    (2000, 100, 300),
+    (3000, 100, 60),
+    (2500, 100, 60),
+    (2000, 100, 0),
+];
+const PATTERN_COMPLETE: Pattern = &[(2000, 120, 60), (2500, 120, 60), (3000, 400, 0)];
+const PATTERN_IDLE_WARNING: Pattern = &[(1500, 500, 250), (1500, 500, 250), (1500, 500, 0)];
+
+fn tone_config(freq: u16) -> pwm::Config {
+    let mut config = pwm::Config::default();
+    config.divider = 64.to_fixed();
+    config.top = (BUZZER_CLOCK / freq as u32 - 1) as u16;
+    config.compare_a = config.top / 2;
+    config
+}
+
+/**
+### Continuous fault tone
+* For panic_handler, PWM keeps running with interrupts disabled
+* Always on, not affected by buzzer setting
+*/
+pub(crate) fn fault_tone(slice: PWM_SLICE0, pin: PIN_16) {
+    let pwm = Pwm::new_output_a(slice, pin, tone_config(FAULT_FREQ));
+    //keep slice running after return
+    core::mem::forget(pwm);
+}
+
+/**
+### Piezo buzzer on PIN_16 (PWM slice 0A)
+* Plays a distinct pattern for each operator alert
+* Alerts are queued while a pattern plays
+*/
+pub(crate) struct Buzzer<'a> {
+    channel: SyncStateChannelReceiver<'a, SyncBuzzerStateEnum>,
+    pwm: Pwm<'a>,
+    enabled: bool,
+}
+
+impl<'a> Buzzer<'a> {
+    pub fn new(pwm: Pwm<'a>, enabled: bool, channels: &'a channels::Channels) -> Self {
+        Self {
+            channel: channels.get_buzzer_rx(),
+            pwm,
+            enabled,
+        }
+    }
+
+    pub async fn buzzer_task(&mut self) -> ! {
+        let rx = self.channel;
+        loop {
+            let pattern = match rx.receive().await {
+                SyncBuzzerStateEnum::Enabled(enabled) => {
+                    self.enabled = enabled;
+                    continue;
+                }
+                SyncBuzzerStateEnum::Start => PATTERN_START,
+                SyncBuzzerStateEnum::PlaceBoard => PATTERN_PLACE_BOARD,
+                SyncBuzzerStateEnum::PeakReached => PATTERN_PEAK_REACHED,
+                SyncBuzzerStateEnum::RemoveBoard => PATTERN_REMOVE_BOARD,
+                SyncBuzzerStateEnum::Complete => PATTERN_COMPLETE,
+                SyncBuzzerStateEnum::IdleWarning => PATTERN_IDLE_WARNING,
+            };
+
+            if self.enabled {
+                self.play(pattern).await;
+            }
+        }
+    }
+
+    async fn play(&mut self, pattern: Pattern) {
+        for &(freq, on, off) in pattern {
+            self.pwm.set_config(&tone_config(freq));
+            Timer::after_millis(on as u64).await;
+            self.pwm.set_config(&pwm::Config::default());
+            Timer::after_millis(off as u64).await;
+        }
+    }
+}
@@ -0,0 +1,54 @@
+/**
+### Outer loop of board/plate cascade
+* PI on board temperature error
+* Output is plate setpoint, limited to `max_over` around board target
+* Integral is held while output is saturated
+*/
+pub(crate) struct Cascade {
+    p: f32,
+    i: f32,
+    max_over: f32,
+    integral: f32,
+}
+
+impl Cascade {
+    pub fn new(p: f32, i: f32, max_over: u16) -> Self {
+        Self {
+            p,
+            i,
+            max_over: max_over as f32,
+            integral: 0.0,
+        }
+    }
+
+    pub fn set_settings(&mut self, p: f32, i: f32, max_over: u16) {
+        self.p = p;
+        self.i = i;
+        self.max_over = max_over as f32;
+    }
+
+    pub fn reset(&mut self) {
+        self.integral = 0.0;
+    }
+
+    pub fn update(&mut self, target: f32, board_temp: f32, dt: f32) -> f32 {
+        let error = target - board_temp;
+        let integral = self.integral + self.i * error * dt;
+        let correction = self.p * error + integral;
+
+        if correction > self.max_over {
+            if error < 0.0 {
+                self.integral = integral;
+            }
+        } else if correction < -self.max_over {
+            if error > 0.0 {
+                self.integral = integral;
+            }
+        } else {
+            self.integral = integral;
+        }
+
+        let plate_target = target + correction.clamp(-self.max_over, self.max_over);
+        plate_target.max(0.0)
+    }
+}
@@ -1,4 +1,5 @@
 use crate::{
+    buzzer::SyncBuzzerStateEnum,
     display::SyncDisplayStateEnum,
     heater::SyncHeatStateEnum,
     menu::SyncMenuStateEnum,
@@ -13,6 +14,7 @@ pub(crate) struct Channels {
     heat: SyncStateChannel<SyncHeatStateEnum>,
     storage: SyncStateChannel<SyncStorageStateEnum>,
     menu: SyncStateChannel<SyncMenuStateEnum>,
+    buzzer: SyncStateChannel<SyncBuzzerStateEnum>,
 }
 
 impl Channels {
@@ -23,6 +25,7 @@ impl Channels {
             heat: SyncStateChannel::<SyncHeatStateEnum>::new(),
             storage: SyncStateChannel::<SyncStorageStateEnum>::new(),
             menu: SyncStateChannel::<SyncMenuStateEnum>::new(),
+            buzzer: SyncStateChannel::<SyncBuzzerStateEnum>::new(),
         }
     }
 
@@ -65,4 +68,12 @@ impl Channels {
     pub fn get_menu_tx(&self) -> SyncStateChannelSender<'_, SyncMenuStateEnum> {
         self.menu.sender()
     }
+
+    pub fn get_buzzer_rx(&self) -> SyncStateChannelReceiver<'_, SyncBuzzerStateEnum> {
+        self.buzzer.receiver()
+    }
+
+    pub fn get_buzzer_tx(&self) -> SyncStateChannelSender<'_, SyncBuzzerStateEnum> {
+        self.buzzer.sender()
+    }
 }
@@ -9,6 +9,7 @@ use simplestaticstring::{format_static, StaticString, ToStaticString};
 use ssd1306::mode::DisplayConfig;
 
 use crate::tools::SyncStateChannelReceiver;
+use crate::zone::AUX_ZONES;
 use crate::{channels, select, temperature};
 
 #[derive(Debug)]
@@ -18,6 +19,13 @@ pub(crate) enum SyncDisplayStateEnum {
     PeakTargetTemp(u16, temperature::TemperatureProfileEnum),
     CurrTargetTemp(u16),
     OutputEnabled(bool),
+    Noise(f32),
+    BoardTemp(Option<u16>),
+    ZoneTemp {
+        number: u8,
+        temp: Option<u16>,
+        output: bool,
+    },
 }
 
 pub(crate) struct Display<'a> {
@@ -59,6 +67,9 @@ impl<'a> Display<'a> {
         let mut curr_target_temp: StaticString<3> = format_static!("000");
         let mut peak_target_temp: StaticString<3> = format_static!("000");
         let mut output_en: StaticString<1> = format_static!(" ");
+        let mut noise: f32 = 0.0;
+        let mut board_temp: Option<u16> = None;
+        let mut zones: [Option<(u16, bool)>; AUX_ZONES] = [None; AUX_ZONES];
 
         loop {
             let time_begin = embassy_time::Instant::now();
@@ -89,6 +100,25 @@ impl<'a> Display<'a> {
                                 false => format_static!(" "),
                             };
                         }
+                        SyncDisplayStateEnum::Noise(x) => {
+                            noise = x;
+                        }
+                        SyncDisplayStateEnum::BoardTemp(x) => {
+                            board_temp = x;
+                        }
+                        SyncDisplayStateEnum::ZoneTemp {
+                            number,
+                            temp,
+                            output,
+                        } => {
+                            //main zone is 1, auxiliary zones start at 2
+                            if let Some(zone) = (number as usize)
+                                .checked_sub(2)
+                                .and_then(|i| zones.get_mut(i))
+                            {
+                                *zone = temp.map(|x| (x, output));
+                            }
+                        }
                     },
                     embassy_futures::select::Either::Second(_delay) => {
                         break;
@@ -103,6 +133,29 @@ impl<'a> Display<'a> {
                 peak_target_temp,
                 output_en
             );
+            let info_line: StaticString<21> = match board_temp {
+                Some(x) => format_static!("board: {:03} noise:{:.1}", x, noise),
+                None => format_static!("noise: {:.1}", noise),
+            };
+
+            //one entry per enabled auxiliary zone, cut at display width
+            //drawn in last menu row, menu shows one row less while zones are enabled
+            let mut zone_line: StaticString<21> = StaticString::default();
+            for (i, zone) in zones.iter().enumerate() {
+                if let Some((temp, output)) = zone {
+                    if write!(
+                        zone_line,
+                        "z{}:{:03}{} ",
+                        i + 2,
+                        temp,
+                        if *output { "*" } else { " " }
+                    )
+                    .is_err()
+                    {
+                        break;
+                    }
+                }
+            }
 
             self.display.clear_buffer();
 
@@ -119,7 +172,29 @@ impl<'a> Display<'a> {
             }
             if embedded_graphics::text::Text::with_baseline(
                 &second_line,
-                Point::new(0, 16),
+                Point::new(0, 12),
+                text_style,
+                text::Baseline::Top,
+            )
+            .draw(&mut self.display)
+            .is_err()
+            {
+                //ignore: draw text failed
+            }
+            if embedded_graphics::text::Text::with_baseline(
+                &zone_line,
+                Point::new(0, 42),
+                text_style,
+                text::Baseline::Top,
+            )
+            .draw(&mut self.display)
+            .is_err()
+            {
+                //ignore: draw text failed
+            }
+            if embedded_graphics::text::Text::with_baseline(
+                &info_line,
+                Point::new(0, 54),
                 text_style,
                 text::Baseline::Top,
             )
@@ -0,0 +1,90 @@
+use bincode::{Decode, Encode};
+use embassy_rp::pwm::{self, Pwm};
+
+const FAN_PWM_TOP: u16 = 4999; //25kHz at 125MHz
+const FAN_RATE_GAIN: f32 = 0.05;
+const FAN_RATE_TAU: f32 = 5.0;
+const FAN_OFF_TEMP: f32 = 50.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Encode, Decode)]
+pub enum FanModeEnum {
+    Auto,
+    On,
+    Off,
+}
+
+/**
+### Cooling fan on PIN_20 (PWM slice 2A)
+* Auto: runs when profile stage requests cooling, duty is integrated on `cooling_rate` error
+* Auto stops below `FAN_OFF_TEMP` and outside cooling stages
+* On: full speed regardless of temperature, Off: never runs
+* Any mode is off while heater has demand outside cooling stages, fan never fights the heater
+*/
+pub(crate) struct Fan<'a> {
+    pwm: Pwm<'a>,
+    pwm_config: pwm::Config,
+    mode: FanModeEnum,
+    cooling_rate: f32,
+    duty: f32,
+    last_temp: Option<f32>,
+    rate: f32,
+}
+
+impl<'a> Fan<'a> {
+    pub fn new(pwm: Pwm<'a>, mode: FanModeEnum, cooling_rate: f32) -> Self {
+        let mut pwm_config = pwm::Config::default();
+        pwm_config.top = FAN_PWM_TOP;
+        pwm_config.compare_a = 0;
+
+        let mut this = Self {
+            pwm,
+            pwm_config,
+            mode,
+            cooling_rate,
+            duty: 0.0,
+            last_temp: None,
+            rate: 0.0,
+        };
+        this.pwm.set_config(&this.pwm_config);
+
+        this
+    }
+
+    pub fn set_settings(&mut self, mode: FanModeEnum, cooling_rate: f32) {
+        self.mode = mode;
+        self.cooling_rate = cooling_rate;
+        self.duty = 0.0;
+    }
+
+    /**
+    ### Updates fan output
+    * `cooling`: profile stage requests cooling
+    * `demand`: heater output demand of all zones
+    */
+    pub fn update(&mut self, temp: f32, cooling: bool, demand: f32, dt: f32) {
+        //filtered cooling rate, positive while temperature falls
+        if let (Some(last_temp), true) = (self.last_temp, dt > 0.0) {
+            let alpha = dt / (FAN_RATE_TAU + dt);
+            self.rate += alpha * ((last_temp - temp) / dt - self.rate);
+        }
+        self.last_temp = Some(temp);
+
+        let duty = match self.mode {
+            _ if demand > 0.0 && !cooling => 0.0,
+            FanModeEnum::Off => 0.0,
+            FanModeEnum::On => 1.0,
+            FanModeEnum::Auto if cooling && temp >= FAN_OFF_TEMP => {
+                let error = self.cooling_rate - self.rate;
+                self.duty = (self.duty + FAN_RATE_GAIN * error * dt).clamp(0.0, 1.0);
+                self.duty
+            }
+            FanModeEnum::Auto => 0.0,
+        };
+        if !cooling {
+            self.duty = 0.0;
+        }
+
+        self.pwm_config.compare_a = (duty * FAN_PWM_TOP as f32) as u16;
+        self.pwm.set_config(&self.pwm_config);
+    }
+}
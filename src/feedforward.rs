@@ -0,0 +1,174 @@
+use micromath::F32Ext;
+
+use crate::plant::PlantModel;
+
+pub(crate) const DUTY_MAP_POINTS: usize = 5;
+pub(crate) const DUTY_MAP_TEMPS: [u16; DUTY_MAP_POINTS] = [50, 100, 150, 200, 250];
+
+const RATE_FILTER: f32 = 0.1;
+const LEARN_BAND: f32 = 1.5;
+const LEARN_RATE_MAX: f32 = 0.05;
+const LEARN_SETTLE: f32 = 30.0;
+const LEARN_RANGE: u16 = 25;
+const LEARN_TAU: f32 = 60.0;
+
+pub(crate) type DutyMap = [Option<f32>; DUTY_MAP_POINTS];
+
+/**
+### Feed-forward duty for PID output
+* Steady-state duty from map of duty vs temperature, gaps are bridged with plant model
+* Ramp duty from plant model: `tau * dT/dt / K`
+* Map is learned from holds where PID settled on target
+*/
+pub(crate) struct FeedForward {
+    enabled: bool,
+    learn: bool,
+    map: DutyMap,
+    model: PlantModel,
+    last_target: Option<f32>,
+    rate: f32,
+    hold_time: f32,
+    learned: bool,
+}
+
+impl FeedForward {
+    pub fn new(enabled: bool, learn: bool, map: DutyMap, model: PlantModel) -> Self {
+        Self {
+            enabled,
+            learn,
+            map,
+            model,
+            last_target: None,
+            rate: 0.0,
+            hold_time: 0.0,
+            learned: false,
+        }
+    }
+
+    pub fn set_settings(&mut self, enabled: bool, learn: bool, map: DutyMap) {
+        self.enabled = enabled;
+        self.learn = learn;
+        self.map = map;
+    }
+
+    pub fn set_model(&mut self, model: PlantModel) {
+        self.model = model;
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn reset(&mut self) {
+        self.last_target = None;
+        self.rate = 0.0;
+        self.hold_time = 0.0;
+    }
+
+    /**
+    ### Map learned since last call
+    * For persisting once a run ends
+    */
+    pub fn take_learned(&mut self) -> Option<DutyMap> {
+        if self.learned {
+            self.learned = false;
+            Some(self.map)
+        } else {
+            None
+        }
+    }
+
+    fn model_slope(&self) -> Option<f32> {
+        if self.model.valid() {
+            Some(1.0 / (self.model.gain * 100.0))
+        } else {
+            None
+        }
+    }
+
+    fn steady_duty(&self, target: f32) -> f32 {
+        let mut lower: Option<(f32, f32)> = None;
+        let mut upper: Option<(f32, f32)> = None;
+        for (temp, duty) in DUTY_MAP_TEMPS.iter().zip(self.map.iter()) {
+            if let Some(duty) = duty {
+                let temp = *temp as f32;
+                if temp <= target {
+                    lower = Some((temp, *duty));
+                } else if upper.is_none() {
+                    upper = Some((temp, *duty));
+                }
+            }
+        }
+
+        match (lower, upper, self.model_slope()) {
+            (Some((t0, d0)), Some((t1, d1)), _) => d0 + (d1 - d0) * (target - t0) / (t1 - t0),
+            (Some((t, d)), None, Some(slope)) | (None, Some((t, d)), Some(slope)) => {
+                d + (target - t) * slope
+            }
+            (Some((_, d)), None, None) | (None, Some((_, d)), None) => d,
+            (None, None, Some(slope)) => (target - self.model.ambient) * slope,
+            (None, None, None) => 0.0,
+        }
+    }
+
+    /**
+    ### Feed-forward duty 0..1 for target
+    * Returns 0 when disabled or target is off
+    */
+    pub fn output(&mut self, target: f32, dt: f32) -> f32 {
+        if let (Some(last_target), true) = (self.last_target, dt > 0.0) {
+            let rate = (target - last_target) / dt;
+            self.rate += (rate - self.rate) * RATE_FILTER;
+        }
+        self.last_target = Some(target);
+
+        if !self.enabled || target <= 0.0 {
+            return 0.0;
+        }
+
+        let ramp = match self.model_slope() {
+            Some(slope) => self.model.tau * self.rate.max(0.0) * slope,
+            None => 0.0,
+        };
+
+        (self.steady_duty(target) + ramp).clamp(0.0, 1.0)
+    }
+
+    /**
+    ### Learns steady-state duty from settled hold
+    * `duty` is the total output applied to the heater
+    * Nearest map point within range is moved towards it
+    */
+    pub fn observe(&mut self, target: f32, temp: f32, duty: f32, dt: f32) {
+        if !self.learn || target <= 0.0 {
+            return;
+        }
+
+        if (target - temp).abs() > LEARN_BAND || self.rate.abs() > LEARN_RATE_MAX {
+            self.hold_time = 0.0;
+            return;
+        }
+
+        self.hold_time += dt;
+        if self.hold_time < LEARN_SETTLE {
+            return;
+        }
+
+        let target_u16 = target as u16;
+        let Some(point) = DUTY_MAP_TEMPS
+            .iter()
+            .position(|x| x.abs_diff(target_u16) <= LEARN_RANGE)
+        else {
+            return;
+        };
+
+        let duty = match self.model_slope() {
+            Some(slope) => duty + (DUTY_MAP_TEMPS[point] as f32 - target) * slope,
+            None => duty,
+        };
+        let alpha = (dt / LEARN_TAU).min(1.0);
+        let current = self.map[point].unwrap_or(duty);
+        self.map[point] = Some((current + (duty - current) * alpha).clamp(0.0, 1.0));
+        self.learned = true;
+    }
+}
@@ -1,17 +1,49 @@
-use core::cmp::{max, min};
-
-use embassy_rp::adc::{Adc, Channel};
-use embassy_rp::pwm::{self, Pwm};
-use embassy_time::Timer;
-use fixed::traits::ToFixed;
-use pid_lite::Controller;
+use core::fmt::Write;
+use embassy_sync::channel::TrySendError;
+use embassy_time::{Duration, Instant, Ticker, Timer};
+use heapless::Deque;
+use simplestaticstring::{format_static, StaticString};
 
+use crate::allocator::{AllocPolicyEnum, PowerAllocator};
+use crate::bangbang::BangBang;
+use crate::buzzer::SyncBuzzerStateEnum;
+use crate::cascade::Cascade;
 use crate::display::SyncDisplayStateEnum;
-use crate::thermistor::Thermistor;
+use crate::fan::{Fan, FanModeEnum};
+use crate::feedforward::{DutyMap, FeedForward};
+use crate::idle::{IdleEventEnum, IdleTimer};
+use crate::interlock::{InterlockActionEnum, InterlockEventEnum, InterlockSettings, Interlocks};
+use crate::jitter::JitterStats;
+use crate::menu::SyncMenuStateEnum;
+use crate::monitor::SystemMonitor;
+use crate::output::{OutputModeEnum, PowerLimit};
+use crate::pid::Controller;
+use crate::plant::PlantModel;
+use crate::schedule::{GainSchedule, PidBand, PID_BANDS};
+use crate::source::{TemperatureSource, ThermistorSource};
+use crate::stats::{Stats, StatsData};
+use crate::storage::SyncStorageStateEnum;
+use crate::temperature::{LagCompensationEnum, SyncSourceEnum};
 use crate::tools::SyncStateChannelReceiver;
 use crate::watchdog::SyncWdStateEnum;
+use crate::zone::{Zone, ZoneSettings, ZONES};
 use crate::{channels, select, storage, temperature, SyncStateChannelSender};
 
+const POST_AMBIENT_DIFF: f32 = 15.0;
+const POST_START_TEMP_MAX: f32 = 300.0; //above any run, plate can't still be this hot
+const POST_PULSE_TIME: Duration = Duration::from_millis(3000);
+const POST_RISE_TIME: Duration = Duration::from_millis(10000);
+const POST_SAMPLE_PERIOD: Duration = Duration::from_millis(250);
+const POST_RISE_MIN: f32 = 1.0;
+const MENU_PENDING_MAX: usize = 4;
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum PostResultEnum {
+    Heated(f32),
+    Warm,
+    Skipped,
+}
+
 #[derive(Debug)]
 pub(crate) enum SyncHeatStateEnum {
     TargetTemp(u16, temperature::TemperatureProfileEnum),
@@ -21,38 +53,146 @@ pub(crate) enum SyncHeatStateEnum {
         pid_i: f32,
         pid_d: f32,
     },
+    PidLimits {
+        i_min: f32,
+        i_max: f32,
+    },
+    PidSchedule {
+        schedule: bool,
+        bands: [PidBand; PID_BANDS],
+    },
+    BangBang {
+        below: f32,
+        above: f32,
+        min_switch: f32,
+    },
     TempSettings {
         wait_time: f32,
         extra_time: f32,
         temp_lead_offset: i16,
         temp_offset: i16,
     },
+    LagCompensation {
+        profile_a: LagCompensationEnum,
+    },
+    BoardProbe {
+        enabled: bool,
+        sync_source: SyncSourceEnum,
+    },
+    Cascade {
+        cascade: bool,
+        cascade_p: f32,
+        cascade_i: f32,
+        cascade_max_over: u16,
+    },
+    AutoTuneSettings {
+        amplitude: f32,
+        hysteresis: f32,
+        cycles: u8,
+    },
+    CharacterizeSettings {
+        duty: f32,
+        lambda: f32,
+    },
+    PlantModel(PlantModel),
+    FeedForward {
+        enabled: bool,
+        learn: bool,
+        map: DutyMap,
+    },
+    Output {
+        mode: OutputModeEnum,
+        window: f32,
+        min_on: f32,
+        min_off: f32,
+    },
+    PowerLimit {
+        max_duty: f32,
+        max_duty_soak: f32,
+        soft_start: f32,
+    },
+    ControlPeriod(u16),
+    Fan {
+        mode: FanModeEnum,
+        cooling_rate: f32,
+    },
+    IdleTimeout(u16),
+    Activity,
+    HeaterWatts(u16),
+    StatsReset,
+    Zone {
+        index: usize,
+        settings: ZoneSettings,
+    },
+    PowerBudget {
+        budget: u16,
+        policy: AllocPolicyEnum,
+    },
+    Interlock {
+        index: usize,
+        settings: InterlockSettings,
+    },
+    ManualLimits {
+        temp_max: u16,
+        time_max: u16,
+    },
+}
+
+/**
+### Heater output stages and safety inputs
+* `zones`: main plate zone first, auxiliary zones following main profile
+* `interlocks`: inputs that force all outputs off while open
+*/
+pub(crate) struct HeaterIo<'a, S> {
+    pub fan: Fan<'a>,
+    pub zones: [Zone<'a, S>; ZONES],
+    pub interlocks: Interlocks<'a>,
 }
 
-pub(crate) struct Heater<'a> {
+pub(crate) struct Heater<'a, S: TemperatureSource> {
     channel: SyncStateChannelReceiver<'a, SyncHeatStateEnum>,
-    target_temp: temperature::TemperatureProfile<'a>,
+    target_temp: temperature::TemperatureProfile,
     pid_use: bool,
     pid_p: f32,
     pid_i: f32,
     pid_d: f32,
+    bang_bang: BangBang,
     controller: Controller,
-    pwm_config: pwm::Config,
-    adc: Adc<'a, embassy_rp::adc::Async>,
-    adc_temp_ch: Channel<'a>,
-    thermistor: &'a Thermistor,
-    mosfet: Pwm<'a>,
+    pid_i_min: f32,
+    pid_i_max: f32,
+    schedule: GainSchedule,
+    feedforward: FeedForward,
+    plant_model: PlantModel,
+    imc_lambda: f32,
+    cascade_use: bool,
+    cascade: Cascade,
+    power_limit: PowerLimit,
+    allocator: PowerAllocator,
+    jitter: JitterStats,
+    board_source: ThermistorSource<'a>,
+    board_probe: bool,
+    post_pulse: bool,
+    idle: IdleTimer,
+    stats: Stats,
+    monitor: SystemMonitor<'a>,
+    fan: Fan<'a>,
+    zones: [Zone<'a, S>; ZONES],
+    interlocks: Interlocks<'a>,
     display_tx: SyncStateChannelSender<'a, SyncDisplayStateEnum>,
+    menu_tx: SyncStateChannelSender<'a, SyncMenuStateEnum>,
+    menu_pending: Deque<SyncMenuStateEnum, MENU_PENDING_MAX>,
+    buzzer_tx: SyncStateChannelSender<'a, SyncBuzzerStateEnum>,
+    storage_tx: SyncStateChannelSender<'a, SyncStorageStateEnum>,
     wd_tx: SyncStateChannelSender<'a, SyncWdStateEnum>,
 }
 
-impl<'a> Heater<'a> {
+impl<'a, S: TemperatureSource> Heater<'a, S> {
     pub fn new(
         startup_storage: &storage::StorageData,
-        adc: Adc<'a, embassy_rp::adc::Async>,
-        adc_temp_ch: Channel<'a>,
-        thermistor: &'a Thermistor,
-        mosfet: Pwm<'a>,
+        startup_stats: StatsData,
+        board_source: ThermistorSource<'a>,
+        monitor: SystemMonitor<'a>,
+        io: HeaterIo<'a, S>,
         channels: &'a channels::Channels,
     ) -> Self {
         let mut this = Self {
@@ -64,156 +204,807 @@ impl<'a> Heater<'a> {
                 startup_storage.temp_extra_time,
                 startup_storage.temp_lead_offset,
                 startup_storage.temp_offset,
-                channels.get_menu_tx(),
             ),
             pid_use: startup_storage.pid,
             pid_p: startup_storage.pid_p,
             pid_i: startup_storage.pid_i,
             pid_d: startup_storage.pid_d,
+            bang_bang: BangBang::new(
+                startup_storage.bang_below,
+                startup_storage.bang_above,
+                startup_storage.bang_min_switch,
+            ),
             controller: Controller::new(
                 0.0f32,
                 startup_storage.pid_p,
                 startup_storage.pid_i,
                 startup_storage.pid_d,
             ),
-            pwm_config: pwm::Config::default(),
-            adc,
-            adc_temp_ch,
-            thermistor,
-            mosfet,
+            pid_i_min: startup_storage.pid_i_min,
+            pid_i_max: startup_storage.pid_i_max,
+            schedule: GainSchedule::new(startup_storage.pid_schedule, startup_storage.pid_bands),
+            feedforward: FeedForward::new(
+                startup_storage.feedforward,
+                startup_storage.feedforward_learn,
+                startup_storage.duty_map,
+                startup_storage.plant_model,
+            ),
+            plant_model: startup_storage.plant_model,
+            imc_lambda: startup_storage.imc_lambda,
+            cascade_use: startup_storage.cascade,
+            cascade: Cascade::new(
+                startup_storage.cascade_p,
+                startup_storage.cascade_i,
+                startup_storage.cascade_max_over,
+            ),
+            power_limit: PowerLimit::new(
+                startup_storage.max_duty,
+                startup_storage.max_duty_soak,
+                startup_storage.soft_start,
+            ),
+            allocator: PowerAllocator::new(
+                startup_storage.power_budget,
+                startup_storage.power_policy,
+            ),
+            jitter: JitterStats::new(Duration::from_millis(startup_storage.control_period as u64)),
+            board_source,
+            board_probe: startup_storage.board_probe,
+            post_pulse: startup_storage.post_pulse,
+            idle: IdleTimer::new(startup_storage.idle_timeout),
+            stats: Stats::new(startup_stats, startup_storage.heater_watts),
+            monitor,
+            fan: io.fan,
+            zones: io.zones,
+            interlocks: io.interlocks,
             display_tx: channels.get_display_tx(),
+            menu_tx: channels.get_menu_tx(),
+            menu_pending: Deque::new(),
+            buzzer_tx: channels.get_buzzer_tx(),
+            storage_tx: channels.get_storage_tx(),
             wd_tx: channels.get_watchdog_tx(),
         };
 
-        this.controller.set_error_sum_limits(Some(0.0), Some(1.0));
-        this.pwm_config.divider = 16.to_fixed();
+        this.target_temp
+            .set_sync_source(startup_storage.sync_source);
+        this.target_temp.set_autotune_settings(
+            startup_storage.autotune_amplitude,
+            startup_storage.autotune_hysteresis,
+            startup_storage.autotune_cycles,
+        );
+        this.target_temp
+            .set_characterize_settings(startup_storage.characterize_duty);
+        this.target_temp
+            .set_lag_compensation(startup_storage.lag_profile_a);
+        this.target_temp
+            .set_plant_model(startup_storage.plant_model, startup_storage.imc_lambda);
+        this.target_temp.set_manual_limits(
+            startup_storage.manual_temp_max,
+            startup_storage.manual_time_max,
+        );
+        this.target_temp.set_cascade(
+            startup_storage.pid && startup_storage.cascade && startup_storage.board_probe,
+        );
+        this.set_integral_limits();
+
+        //all zone slices are configured, stagger zone periods against main zone
+        for (i, zone) in this.zones.iter_mut().enumerate() {
+            zone.set_phase(PowerAllocator::phase(i));
+        }
 
         this
     }
 
+    /**
+    ### Integral range
+    * Configured in duty units
+    * With feed-forward integral only corrects residual error, it may pull output down as much as up
+    */
+    fn set_integral_limits(&mut self) {
+        let i_min = if self.feedforward.enabled() {
+            self.pid_i_min.min(-self.pid_i_max)
+        } else {
+            self.pid_i_min
+        };
+        self.controller
+            .set_integral_limits(Some(i_min), Some(self.pid_i_max));
+    }
+
+    /**
+    ### Power-on self test
+    * Runs before any command is handled, profiles can't start until it passes
+    * Plate sensor must read and be near MCU die temperature (ambient)
+    * Optional heater pulse must give a measurable rise, catches open heater, fuse or mosfet
+    * Pulse goes through output stage and power limit, required rise scales with delivered duty
+    * Pulse is skipped while plate is still warm from a previous run
+    * Interlocks are sampled every pulse sample, an open input stops the pulse and skips the check
+    * Start above `POST_START_TEMP_MAX` is implausible, sensor is faulty
+    * Failures panic, result is shown on display and in diagnostics
+    */
+    async fn post(&mut self) {
+        if self
+            .display_tx
+            .try_send(SyncDisplayStateEnum::Status(format_static!("POST...")))
+            .is_err()
+        {
+            //ignore: msg dropped
+        }
+
+        let start_temp = match self.zones[0].read_source().await {
+            Ok(x) => x.temp,
+            Err(fault) => panic!("POST: sensor\n{:?}!", fault),
+        };
+        let ambient = self.monitor.sample().await.die_temp;
+        if start_temp < ambient - POST_AMBIENT_DIFF {
+            panic!("POST: sensor\n{:.1} < mcu {:.1}!", start_temp, ambient);
+        }
+        if start_temp > POST_START_TEMP_MAX {
+            panic!(
+                "POST: sensor\n{:.1} > {:.0}!",
+                start_temp, POST_START_TEMP_MAX
+            );
+        }
+        let warm = start_temp > ambient + POST_AMBIENT_DIFF;
+
+        let result = if warm {
+            PostResultEnum::Warm
+        } else if !self.post_pulse || self.interlocks.is_open() {
+            PostResultEnum::Skipped
+        } else {
+            //pulse, then watch for delayed rise
+            let begin = Instant::now();
+            let dt = POST_SAMPLE_PERIOD.as_micros() as f32 / 1_000_000.0;
+            let mut max_temp = start_temp;
+            let mut delivered = 0.0;
+            let mut interrupted = false;
+            while begin.elapsed() < POST_RISE_TIME {
+                //interlock opened during pulse, stop heating and skip the rise check
+                for event in self.interlocks.update(dt).into_iter().flatten() {
+                    self.interlock_event(event);
+                }
+                if self.interlocks.is_open() {
+                    self.zones[0].set_output(0.0);
+                    interrupted = true;
+                    break;
+                }
+
+                self.power_limit.update(dt);
+                let demand = if begin.elapsed() < POST_PULSE_TIME {
+                    self.power_limit.limit(1.0, false)
+                } else {
+                    0.0
+                };
+                self.zones[0].set_output(demand);
+
+                Timer::after(POST_SAMPLE_PERIOD).await;
+                delivered += demand * dt;
+                match self.zones[0].read_source().await {
+                    Ok(x) => max_temp = max_temp.max(x.temp),
+                    Err(fault) => panic!("POST: sensor\n{:?}!", fault),
+                }
+
+                //feed wd
+                self.wd_tx
+                    .try_send(SyncWdStateEnum::HeatTask)
+                    .expect("post: wdtx fail");
+            }
+            self.zones[0].set_output(0.0);
+
+            //soft start may limit the pulse, expect rise of delivered part only
+            let full = self.power_limit.max_duty() * POST_PULSE_TIME.as_millis() as f32 / 1000.0;
+            let rise_min = POST_RISE_MIN * (delivered / full).min(1.0);
+            let rise = max_temp - start_temp;
+            if interrupted || delivered <= 0.0 {
+                PostResultEnum::Skipped
+            } else if rise < rise_min {
+                panic!("POST: no heat\nrise {:.1}C!", rise);
+            } else {
+                PostResultEnum::Heated(rise)
+            }
+        };
+
+        let status: StaticString<100> = match result {
+            PostResultEnum::Heated(rise) => format_static!("POST ok +{:.1}C", rise),
+            PostResultEnum::Warm => format_static!("POST ok (warm)"),
+            PostResultEnum::Skipped => format_static!("POST ok (no pulse)"),
+        };
+        if self
+            .display_tx
+            .try_send(SyncDisplayStateEnum::Status(status))
+            .is_err()
+        {
+            //ignore: msg dropped
+        }
+        self.notify_menu(SyncMenuStateEnum::Post(result));
+    }
+
+    /**
+    ### Sends state change to menu without blocking control loop
+    * Message is kept and retried every step while menu channel is full
+    */
+    fn notify_menu(&mut self, msg: SyncMenuStateEnum) {
+        if self.menu_pending.push_back(msg).is_err() {
+            //ignore: msg dropped
+        }
+        self.flush_menu();
+    }
+
+    fn flush_menu(&mut self) {
+        while let Some(msg) = self.menu_pending.pop_front() {
+            if let Err(TrySendError::Full(msg)) = self.menu_tx.try_send(msg) {
+                if self.menu_pending.push_front(msg).is_err() {
+                    //ignore: msg dropped
+                }
+                break;
+            }
+        }
+    }
+
     pub async fn heat_task(&mut self) -> ! {
+        self.post().await;
+
         let rx = self.channel;
-        let mut time_begin = embassy_time::Instant::now();
-        let mut last_temp_target = 0;
+        let mut ticker = Ticker::every(self.jitter.period());
+        let mut last_tick = Instant::now();
         loop {
-            //recv updates or sleep
+            //recv updates or run control step
             let recv_fut = rx.receive();
-            let sleep_fut = Timer::after_millis(100);
-            let select_fut = select!(recv_fut, sleep_fut,);
+            let tick_fut = ticker.next();
+            let select_fut = select!(recv_fut, tick_fut,);
             match select_fut.await {
-                embassy_futures::select::Either::First(state) => match state {
-                    SyncHeatStateEnum::TargetTemp(temp, prof) => {
-                        self.target_temp.set_profile(prof);
-                        self.target_temp.set_peak(temp);
-                        self.target_temp.reset();
-                        self.controller.reset();
-                    }
-                    SyncHeatStateEnum::Pid {
-                        pid,
-                        pid_p,
-                        pid_i,
-                        pid_d,
-                    } => {
-                        self.pid_use = pid;
-                        self.pid_p = pid_p;
-                        self.pid_i = pid_i;
-                        self.pid_d = pid_d;
-                        self.controller.set_proportional_gain(self.pid_p);
-                        self.controller.set_integral_gain(self.pid_i);
-                        self.controller.set_derivative_gain(self.pid_d);
-                        self.controller.reset();
-                    }
-                    SyncHeatStateEnum::TempSettings {
-                        wait_time,
-                        extra_time,
-                        temp_lead_offset,
-                        temp_offset,
-                    } => {
-                        self.target_temp.set_settings(
+                embassy_futures::select::Either::First(state) => {
+                    //any command counts as operator activity
+                    self.idle.activity();
+                    match state {
+                        SyncHeatStateEnum::TargetTemp(temp, prof) => {
+                            self.target_temp.set_profile(prof);
+                            self.target_temp.set_peak(temp);
+                            self.target_temp.reset();
+                            self.controller.reset();
+                            self.bang_bang.reset();
+                            self.feedforward.reset();
+                            self.power_limit.restart();
+                            for zone in self.zones.iter_mut() {
+                                zone.reset();
+                            }
+
+                            if temp > 0
+                                && self.buzzer_tx.try_send(SyncBuzzerStateEnum::Start).is_err()
+                            {
+                                //ignore: msg dropped
+                            }
+
+                            //persist map learned in previous run
+                            if let Some(map) = self.feedforward.take_learned() {
+                                if self
+                                    .menu_tx
+                                    .try_send(SyncMenuStateEnum::DutyMap(map))
+                                    .is_err()
+                                {
+                                    //ignore: msg dropped
+                                }
+                            }
+                        }
+                        SyncHeatStateEnum::Pid {
+                            pid,
+                            pid_p,
+                            pid_i,
+                            pid_d,
+                        } => {
+                            self.pid_use = pid;
+                            self.pid_p = pid_p;
+                            self.pid_i = pid_i;
+                            self.pid_d = pid_d;
+                            //bumpless: integral is kept in output units
+                            self.controller.set_proportional_gain(self.pid_p);
+                            self.controller.set_integral_gain(self.pid_i);
+                            self.controller.set_derivative_gain(self.pid_d);
+                        }
+                        SyncHeatStateEnum::PidLimits { i_min, i_max } => {
+                            self.pid_i_min = i_min;
+                            self.pid_i_max = i_max;
+                            self.set_integral_limits();
+                        }
+                        SyncHeatStateEnum::PidSchedule { schedule, bands } => {
+                            self.schedule.set_bands(schedule, bands);
+                        }
+                        SyncHeatStateEnum::BangBang {
+                            below,
+                            above,
+                            min_switch,
+                        } => {
+                            self.bang_bang.set_settings(below, above, min_switch);
+                        }
+                        SyncHeatStateEnum::TempSettings {
                             wait_time,
                             extra_time,
                             temp_lead_offset,
                             temp_offset,
-                        );
-                    }
-                },
-                embassy_futures::select::Either::Second(()) => {}
-            }
-
-            let time_elapsed = embassy_time::Instant::now() - time_begin;
-            if time_elapsed.as_millis() > 10 {
-                //read current temp
-                let temp_val = self
-                    .adc
-                    .read(&mut self.adc_temp_ch)
-                    .await
-                    .expect("heat_task: temp fail");
-                let current_temp = self.thermistor.calc_temp(temp_val);
-                let current_temp_u16 = current_temp as u16;
-
-                //calc corrections
-                self.target_temp.update(
-                    time_elapsed.into(),
-                    current_temp_u16,
-                    self.pwm_config.compare_a > 0,
-                );
-                let current_temp_target = self.target_temp.get_current_target().await;
-                self.pwm_config.compare_a = if !self.pid_use {
-                    if current_temp_u16 < current_temp_target {
-                        self.pwm_config.top
-                    } else {
-                        0
+                        } => {
+                            self.target_temp.set_settings(
+                                wait_time,
+                                extra_time,
+                                temp_lead_offset,
+                                temp_offset,
+                            );
+                        }
+                        SyncHeatStateEnum::LagCompensation { profile_a } => {
+                            self.target_temp.set_lag_compensation(profile_a);
+                        }
+                        SyncHeatStateEnum::BoardProbe {
+                            enabled,
+                            sync_source,
+                        } => {
+                            self.board_probe = enabled;
+                            self.target_temp.set_sync_source(sync_source);
+                        }
+                        SyncHeatStateEnum::Cascade {
+                            cascade,
+                            cascade_p,
+                            cascade_i,
+                            cascade_max_over,
+                        } => {
+                            self.cascade_use = cascade;
+                            self.cascade
+                                .set_settings(cascade_p, cascade_i, cascade_max_over);
+                            self.cascade.reset();
+                        }
+                        SyncHeatStateEnum::AutoTuneSettings {
+                            amplitude,
+                            hysteresis,
+                            cycles,
+                        } => {
+                            self.target_temp
+                                .set_autotune_settings(amplitude, hysteresis, cycles);
+                        }
+                        SyncHeatStateEnum::CharacterizeSettings { duty, lambda } => {
+                            self.imc_lambda = lambda;
+                            self.target_temp.set_characterize_settings(duty);
+                            self.target_temp
+                                .set_plant_model(self.plant_model, self.imc_lambda);
+                        }
+                        SyncHeatStateEnum::PlantModel(model) => {
+                            self.plant_model = model;
+                            self.feedforward.set_model(model);
+                            self.target_temp
+                                .set_plant_model(self.plant_model, self.imc_lambda);
+                        }
+                        SyncHeatStateEnum::FeedForward {
+                            enabled,
+                            learn,
+                            map,
+                        } => {
+                            self.feedforward.set_settings(enabled, learn, map);
+                            self.set_integral_limits();
+                        }
+                        SyncHeatStateEnum::Output {
+                            mode,
+                            window,
+                            min_on,
+                            min_off,
+                        } => {
+                            for zone in self.zones.iter_mut() {
+                                zone.set_output_settings(mode, window, min_on, min_off);
+                            }
+                        }
+                        SyncHeatStateEnum::PowerLimit {
+                            max_duty,
+                            max_duty_soak,
+                            soft_start,
+                        } => {
+                            self.power_limit
+                                .set_settings(max_duty, max_duty_soak, soft_start);
+                        }
+                        SyncHeatStateEnum::ControlPeriod(period) => {
+                            self.jitter.set_period(Duration::from_millis(period as u64));
+                            ticker = Ticker::every(self.jitter.period());
+                            last_tick = Instant::now();
+                        }
+                        SyncHeatStateEnum::Fan { mode, cooling_rate } => {
+                            self.fan.set_settings(mode, cooling_rate);
+                        }
+                        SyncHeatStateEnum::IdleTimeout(timeout) => {
+                            self.idle.set_timeout(timeout);
+                        }
+                        SyncHeatStateEnum::Activity => {}
+                        SyncHeatStateEnum::HeaterWatts(watts) => {
+                            self.stats.set_watts(watts);
+                        }
+                        SyncHeatStateEnum::StatsReset => {
+                            self.stats.reset();
+                        }
+                        SyncHeatStateEnum::Zone { index, settings } => {
+                            //settings index 0 is first auxiliary zone
+                            if let Some(zone) = self.zones.get_mut(index + 1) {
+                                zone.set_settings(settings);
+                            }
+                        }
+                        SyncHeatStateEnum::PowerBudget { budget, policy } => {
+                            self.allocator.set_settings(budget, policy);
+                        }
+                        SyncHeatStateEnum::Interlock { index, settings } => {
+                            self.interlocks.set_settings(index, settings);
+                        }
+                        SyncHeatStateEnum::ManualLimits { temp_max, time_max } => {
+                            self.target_temp.set_manual_limits(temp_max, time_max);
+                        }
                     }
-                } else {
-                    if current_temp_target != last_temp_target {
-                        last_temp_target = current_temp_target;
-                        self.controller.set_target(current_temp_target as f32);
-                        self.controller.reset();
+                }
+                embassy_futures::select::Either::Second(()) => {
+                    let now = Instant::now();
+                    self.jitter.record(now - last_tick);
+                    last_tick = now;
+
+                    self.control_step(self.jitter.period()).await;
+                }
+            }
+        }
+    }
+
+    /**
+    ### Handles interlock input change
+    * Controllers restart from zero and soft start ramps again after a pause
+    */
+    fn interlock_event(&mut self, event: InterlockEventEnum) {
+        let status: StaticString<100> = match event {
+            InterlockEventEnum::Opened(input, action) => {
+                self.stats.interlock_trip(input, action);
+                self.controller.reset();
+                self.bang_bang.reset();
+                self.power_limit.restart();
+                for zone in self.zones.iter_mut() {
+                    zone.reset();
+                }
+                format_static!(
+                    "Interlock {} open: {}",
+                    input,
+                    match action {
+                        InterlockActionEnum::Pause => "paused",
+                        InterlockActionEnum::Abort => "abort",
                     }
+                )
+            }
+            InterlockEventEnum::Closed(input) => format_static!("Interlock {} closed", input),
+        };
+        if self
+            .display_tx
+            .try_send(SyncDisplayStateEnum::Status(status))
+            .is_err()
+        {
+            //ignore: msg dropped
+        }
+    }
 
-                    let raw = self
-                        .controller
-                        .update_elapsed(current_temp, time_elapsed.into())
-                        * self.pwm_config.top as f32;
+    /**
+    ### Control step
+    * Runs on fixed period ticker, `time_elapsed` is nominal period
+    */
+    async fn control_step(&mut self, time_elapsed: embassy_time::Duration) {
+        //menu messages left over from previous steps
+        self.flush_menu();
 
-                    max(0, min(raw as u16, self.pwm_config.top))
-                };
+        //safety interlocks first, open input forces all outputs off before slow sensor reads
+        let dt = time_elapsed.as_millis() as f32 / 1000.0;
+        for event in self.interlocks.update(dt).into_iter().flatten() {
+            self.interlock_event(event);
+        }
+        let paused = self.interlocks.is_open();
+        if paused {
+            for zone in self.zones.iter_mut() {
+                zone.set_output(0.0);
+            }
+        }
+
+        //read all zone sensors before new outputs are set, main zone is always enabled
+        let mut readings = [None; ZONES];
+        for (zone, reading) in self.zones.iter_mut().zip(readings.iter_mut()) {
+            *reading = zone.read().await;
+        }
+        let reading = readings[0].expect("main zone disabled");
+        let current_temp = reading.temp;
+        let current_temp_u16 = current_temp as u16;
+        let current_noise = reading.noise;
 
-                //set mosfet
-                self.mosfet.set_config(&self.pwm_config);
+        let board_temp = if self.board_probe {
+            match self.board_source.read().await {
+                Ok(x) => Some(x.temp as u16),
+                Err(fault) => panic!("Board sensor fault\n{:?}!", fault),
+            }
+        } else {
+            None
+        };
 
-                time_begin = embassy_time::Instant::now();
+        //abort drops the run, pause holds it
+        if self.interlocks.action() == Some(InterlockActionEnum::Abort)
+            && self.target_temp.peak() > 0
+        {
+            self.target_temp.set_peak(0);
+            if self
+                .display_tx
+                .try_send(SyncDisplayStateEnum::Status(format_static!(
+                    "Interlock: run aborted"
+                )))
+                .is_err()
+            {
+                //ignore: msg dropped
+            }
+            self.notify_menu(SyncMenuStateEnum::InterlockAbort);
+        }
 
-                //send updates
+        //identification can't run with less duty than it requests
+        if let (true, Some(duty)) = (
+            self.target_temp.is_identification(),
+            self.target_temp.get_output_override(),
+        ) {
+            if duty > self.power_limit.max_duty() {
+                self.target_temp
+                    .set_profile(temperature::TemperatureProfileEnum::Static);
+                self.target_temp.set_peak(0);
                 if self
                     .display_tx
-                    .try_send(SyncDisplayStateEnum::CurrTemp(current_temp_u16))
+                    .try_send(SyncDisplayStateEnum::Status(format_static!(
+                        "Test duty > max duty"
+                    )))
                     .is_err()
                 {
                     //ignore: msg dropped
                 }
+                self.notify_menu(SyncMenuStateEnum::IdentifyRefused);
+            }
+        }
+
+        //idle auto-off
+        match self.idle.update(dt, self.target_temp.is_idle_hold()) {
+            Some(IdleEventEnum::Warn) => {
                 if self
                     .display_tx
-                    .try_send(SyncDisplayStateEnum::CurrTargetTemp(current_temp_target))
+                    .try_send(SyncDisplayStateEnum::Status(format_static!(
+                        "Idle: heater off soon"
+                    )))
                     .is_err()
                 {
                     //ignore: msg dropped
                 }
+                if self
+                    .buzzer_tx
+                    .try_send(SyncBuzzerStateEnum::IdleWarning)
+                    .is_err()
+                {
+                    //ignore: msg dropped
+                }
+            }
+            Some(IdleEventEnum::Off) => {
+                self.target_temp.set_peak(0);
                 if self
                     .display_tx
-                    .try_send(SyncDisplayStateEnum::OutputEnabled(
-                        self.pwm_config.compare_a > 0,
-                    ))
+                    .try_send(SyncDisplayStateEnum::Status(format_static!(
+                        "Idle: heater off"
+                    )))
                     .is_err()
                 {
                     //ignore: msg dropped
                 }
+                self.notify_menu(SyncMenuStateEnum::IdleOff);
+            }
+            None => {}
+        }
 
-                //feed wd
-                self.wd_tx
-                    .try_send(SyncWdStateEnum::HeatTask)
-                    .expect("heat_task: wdtx fail");
+        //calc corrections
+        let cascade_active = self.pid_use && self.cascade_use && board_temp.is_some();
+        self.target_temp.set_cascade(cascade_active);
+        //profile time is held while paused
+        self.target_temp.update(
+            if paused {
+                core::time::Duration::ZERO
+            } else {
+                time_elapsed.into()
+            },
+            current_temp,
+            board_temp,
+            !paused && self.zones[0].output_enabled(),
+        );
+        let current_temp_target = self.target_temp.get_current_target();
+        match self.target_temp.take_menu() {
+            //progress is superseded by the next one, results are queued
+            Some(msg @ SyncMenuStateEnum::Characterize { done: false, .. }) => {
+                if self.menu_tx.try_send(msg).is_err() {
+                    //ignore: msg dropped
+                }
             }
+            Some(msg) => self.notify_menu(msg),
+            None => {}
         }
+        if let Some(alert) = self.target_temp.take_alert() {
+            if alert == SyncBuzzerStateEnum::Complete {
+                self.stats.run_complete();
+            }
+            if self.buzzer_tx.try_send(alert).is_err() {
+                //ignore: msg dropped
+            }
+        }
+        let output_override = match paused {
+            true => None,
+            false => self.target_temp.get_output_override(),
+        };
+        let soak = self.target_temp.is_soak();
+        self.power_limit.update(dt);
+        let demand = if paused {
+            0.0
+        } else if let Some(duty) = output_override {
+            if self.target_temp.is_identification() {
+                //fits need the requested duty, soft start and soak cap are skipped
+                duty.min(self.power_limit.max_duty())
+            } else {
+                self.power_limit.limit(duty, soak)
+            }
+        } else if !self.pid_use {
+            let demand = if self
+                .bang_bang
+                .update(current_temp_target as f32, current_temp, dt)
+            {
+                1.0
+            } else {
+                0.0
+            };
+            self.power_limit.limit(demand, soak)
+        } else {
+            //integral is kept across ramp steps, derivative is on measurement
+            self.controller.set_target(current_temp_target as f32);
+
+            //outer loop: board temp -> plate setpoint
+            let mut plate_target = current_temp_target as f32;
+            if let (true, Some(board_temp)) = (cascade_active, board_temp) {
+                plate_target = if current_temp_target > 0 {
+                    self.cascade
+                        .update(current_temp_target as f32, board_temp as f32, dt)
+                } else {
+                    0.0
+                };
+                self.controller.set_target(plate_target);
+            }
+
+            //gain schedule, integral is kept in output units so switching is bumpless
+            let (pid_p, pid_i, pid_d) = self
+                .schedule
+                .gains(plate_target)
+                .unwrap_or((self.pid_p, self.pid_i, self.pid_d));
+            self.controller.set_proportional_gain(pid_p);
+            self.controller.set_integral_gain(pid_i);
+            self.controller.set_derivative_gain(pid_d);
+
+            let feedforward = self.feedforward.output(plate_target, dt);
+            let duty = self.power_limit.limit(
+                feedforward
+                    + self
+                        .controller
+                        .update_elapsed(current_temp, time_elapsed.into()),
+                soak,
+            );
+            self.feedforward
+                .observe(plate_target, current_temp, duty, dt);
+
+            duty
+        };
+
+        //auxiliary zones follow main target with own offset and gains
+        let mut demands = [0.0; ZONES];
+        demands[0] = demand;
+        let temp_limit = self.target_temp.temp_limit();
+        for (zone, zone_demand) in self.zones[1..].iter_mut().zip(demands[1..].iter_mut()) {
+            let zone_target = if paused { 0 } else { current_temp_target };
+            let duty = zone.demand(zone_target, temp_limit, output_override, dt);
+            *zone_demand = self.power_limit.limit(duty, soak);
+        }
+
+        //share supply budget between zones
+        let demands = self.allocator.allocate(demands);
+        let demand = demands[0];
+
+        //set outputs
+        for (zone, zone_demand) in self.zones.iter_mut().zip(&demands) {
+            zone.set_output(*zone_demand);
+        }
+
+        //usage statistics, flushed rarely to spare flash
+        self.stats.update(demand, dt);
+        if let Some(data) = self.stats.take_flush() {
+            if self
+                .storage_tx
+                .try_send(SyncStorageStateEnum::WriteStats(data))
+                .is_err()
+            {
+                //retried next step
+                self.stats.flush_later();
+            }
+        }
+
+        //set fan, auto mode runs only in cooling stages, heater demand interlocks all modes
+        let cooling = self.target_temp.is_cooldown();
+        self.fan
+            .update(current_temp, cooling, demands.iter().sum(), dt);
+
+        //send updates
+        if self
+            .display_tx
+            .try_send(SyncDisplayStateEnum::CurrTemp(current_temp_u16))
+            .is_err()
+        {
+            //ignore: msg dropped
+        }
+        if self
+            .display_tx
+            .try_send(SyncDisplayStateEnum::BoardTemp(board_temp))
+            .is_err()
+        {
+            //ignore: msg dropped
+        }
+        if self
+            .display_tx
+            .try_send(SyncDisplayStateEnum::Noise(current_noise))
+            .is_err()
+        {
+            //ignore: msg dropped
+        }
+        if self
+            .display_tx
+            .try_send(SyncDisplayStateEnum::CurrTargetTemp(current_temp_target))
+            .is_err()
+        {
+            //ignore: msg dropped
+        }
+        if self
+            .display_tx
+            .try_send(SyncDisplayStateEnum::OutputEnabled(
+                self.zones[0].output_enabled(),
+            ))
+            .is_err()
+        {
+            //ignore: msg dropped
+        }
+        for zone in self.zones[1..].iter() {
+            if self
+                .display_tx
+                .try_send(SyncDisplayStateEnum::ZoneTemp {
+                    number: zone.number(),
+                    temp: zone.temp(),
+                    output: zone.output_enabled(),
+                })
+                .is_err()
+            {
+                //ignore: msg dropped
+            }
+        }
+
+        //check controller health
+        if let Some(status) = self.monitor.poll().await {
+            if self
+                .menu_tx
+                .try_send(SyncMenuStateEnum::Diagnostics {
+                    die_temp: status.die_temp,
+                    vsys: status.vsys,
+                })
+                .is_err()
+            {
+                //ignore: msg dropped
+            }
+            if self
+                .menu_tx
+                .try_send(SyncMenuStateEnum::LoopJitter(self.jitter.report()))
+                .is_err()
+            {
+                //ignore: msg dropped
+            }
+            if self
+                .menu_tx
+                .try_send(SyncMenuStateEnum::Stats(self.stats.data()))
+                .is_err()
+            {
+                //ignore: msg dropped
+            }
+        }
+
+        //feed wd
+        self.wd_tx
+            .try_send(SyncWdStateEnum::HeatTask)
+            .expect("heat_task: wdtx fail");
     }
 }
@@ -0,0 +1,109 @@
+use bincode::{Decode, Encode};
+use embassy_rp::gpio::Input;
+use reflow_plate_logic::interlock::{InterlockEdgeEnum, InterlockFilter};
+
+pub(crate) const INTERLOCKS: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Encode, Decode)]
+pub(crate) enum InterlockActionEnum {
+    Pause,
+    Abort,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Encode, Decode)]
+pub(crate) struct InterlockSettings {
+    pub enabled: bool,
+    pub action: InterlockActionEnum,
+}
+
+impl Default for InterlockSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            action: InterlockActionEnum::Pause,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum InterlockEventEnum {
+    Opened(u8, InterlockActionEnum),
+    Closed(u8),
+}
+
+/**
+### Safety interlock inputs on PIN_5, PIN_6
+* Normally closed contact to GND, open or broken wire reads high
+* Debounced by `InterlockFilter`, disabled inputs are ignored
+* Pause: outputs off and profile time held until closed, Abort: run target drops to 0
+*/
+pub(crate) struct Interlocks<'a> {
+    inputs: [Input<'a>; INTERLOCKS],
+    settings: [InterlockSettings; INTERLOCKS],
+    filter: InterlockFilter<INTERLOCKS>,
+}
+
+impl<'a> Interlocks<'a> {
+    pub fn new(inputs: [Input<'a>; INTERLOCKS], settings: [InterlockSettings; INTERLOCKS]) -> Self {
+        //inputs open at boot count as open without event
+        let mut open = [false; INTERLOCKS];
+        for ((open, input), settings) in open.iter_mut().zip(inputs.iter()).zip(settings.iter()) {
+            *open = settings.enabled && input.is_high();
+        }
+
+        Self {
+            inputs,
+            settings,
+            filter: InterlockFilter::new(open),
+        }
+    }
+
+    pub fn set_settings(&mut self, index: usize, settings: InterlockSettings) {
+        if let Some(x) = self.settings.get_mut(index) {
+            *x = settings;
+            if !settings.enabled {
+                self.filter.disable(index);
+            }
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.filter.is_open()
+    }
+
+    /**
+    ### Strongest action of open inputs
+    * Abort wins over pause
+    */
+    pub fn action(&self) -> Option<InterlockActionEnum> {
+        self.filter
+            .open()
+            .into_iter()
+            .zip(self.settings.iter())
+            .filter(|(open, _)| *open)
+            .map(|(_, settings)| settings.action)
+            .max_by_key(|action| *action == InterlockActionEnum::Abort)
+    }
+
+    /**
+    ### Samples inputs
+    * Returns state change of each input in this step
+    */
+    pub fn update(&mut self, dt: f32) -> [Option<InterlockEventEnum>; INTERLOCKS] {
+        let high = core::array::from_fn(|i| self.inputs[i].is_high());
+        let enabled = self.settings.map(|x| x.enabled);
+        let edges = self.filter.update(high, enabled, dt);
+
+        let mut events = [None; INTERLOCKS];
+        for (i, (event, edge)) in events.iter_mut().zip(edges).enumerate() {
+            *event = edge.map(|edge| match edge {
+                InterlockEdgeEnum::Opened => {
+                    InterlockEventEnum::Opened(i as u8 + 1, self.settings[i].action)
+                }
+                InterlockEdgeEnum::Closed => InterlockEventEnum::Closed(i as u8 + 1),
+            });
+        }
+
+        events
+    }
+}
@@ -0,0 +1,78 @@
+use embassy_time::Duration;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct JitterReport {
+    pub mean_us: u32,
+    pub max_us: u32,
+    pub overruns: u32,
+}
+
+/**
+### Control loop timing statistics
+* Deviation of measured step interval from nominal period
+* Overrun: step started more than a full period late
+* Mean is over last report window, max and overruns since period was set
+*/
+pub(crate) struct JitterStats {
+    period: Duration,
+    sum_us: u64,
+    max_us: u32,
+    count: u32,
+    overruns: u32,
+}
+
+impl JitterStats {
+    pub fn new(period: Duration) -> Self {
+        Self {
+            period,
+            sum_us: 0,
+            max_us: 0,
+            count: 0,
+            overruns: 0,
+        }
+    }
+
+    pub fn set_period(&mut self, period: Duration) {
+        self.period = period;
+        self.reset();
+    }
+
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    pub fn reset(&mut self) {
+        self.sum_us = 0;
+        self.max_us = 0;
+        self.count = 0;
+        self.overruns = 0;
+    }
+
+    pub fn record(&mut self, interval: Duration) {
+        let interval_us = interval.as_micros();
+        let period_us = self.period.as_micros();
+        let deviation = interval_us.abs_diff(period_us) as u32;
+
+        self.sum_us += deviation as u64;
+        self.max_us = self.max_us.max(deviation);
+        self.count += 1;
+        if interval_us >= 2 * period_us {
+            self.overruns += 1;
+        }
+    }
+
+    pub fn report(&mut self) -> JitterReport {
+        let report = JitterReport {
+            mean_us: if self.count > 0 {
+                (self.sum_us / self.count as u64) as u32
+            } else {
+                0
+            },
+            max_us: self.max_us,
+            overruns: self.overruns,
+        };
+        self.sum_us = 0;
+        self.count = 0;
+        report
+    }
+}
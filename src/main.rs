@@ -6,20 +6,38 @@ use embassy_rp::adc::{self, Adc, Channel, Config};
 use embassy_rp::gpio::{Input, Level, Output, Pull};
 use embassy_rp::peripherals::I2C0;
 use embassy_rp::pwm::{self, Pwm};
-use embassy_rp::{bind_interrupts, flash, i2c};
+use embassy_rp::{bind_interrupts, flash, i2c, spi};
 
+mod allocator;
+mod autotune;
+mod bangbang;
+mod buzzer;
+mod cascade;
 mod channels;
 mod display;
+mod fan;
+mod feedforward;
 mod heater;
+mod interlock;
+mod jitter;
 mod menu;
+mod monitor;
+mod output;
 mod panic;
+mod plant;
+mod sampling;
+mod schedule;
+mod source;
+mod stats;
 mod storage;
 mod temperature;
 mod thermistor;
 mod tools;
 mod watchdog;
+mod zone;
 
 use display::print_low_level;
+use reflow_plate_logic::{idle, pid, thermocouple};
 use tools::{wait_for_each_state, SyncStateChannelSender};
 
 bind_interrupts!(struct Irqs {
@@ -34,10 +52,22 @@ bind_interrupts!(struct Irqs {
 * No panic allowed
 */
 fn reset_peripherals_on_exception(peripherals: embassy_rp::Peripherals) {
-    let mut mosfet = Output::new(peripherals.PIN_22, Level::Low);
+    //zone outputs, see zone::ZONES
+    let mut zones = [
+        Output::new(peripherals.PIN_22, Level::Low),
+        Output::new(peripherals.PIN_14, Level::Low),
+    ];
+    let mut fan = Output::new(peripherals.PIN_20, Level::Low);
     let mut led = Output::new(peripherals.PIN_25, Level::Low);
 
-    mosfet.set_low();
+    for zone in zones.iter_mut() {
+        zone.set_low();
+    }
+    fan.set_low();
+
+    stats::PendingFaults::new(peripherals.WATCHDOG).record();
+
+    buzzer::fault_tone(peripherals.PWM_SLICE0, peripherals.PIN_16);
 
     led.set_high();
     cortex_m::asm::delay(8_000_000);
@@ -55,11 +85,54 @@ async fn main_loop(_spawner: Spawner) -> ! {
 
     let mut flash = flash::Flash::new_blocking(peripherals.FLASH);
     let startup_storage = storage::Storage::flash_read(&mut flash);
+    let mut startup_stats = storage::Storage::stats_read(&mut flash);
+
+    //faults of previous boots are counted once flash is safe to write
+    let mut pending_faults = stats::PendingFaults::new(peripherals.WATCHDOG);
+    if pending_faults.count() > 0 {
+        startup_stats.faults = startup_stats.faults.saturating_add(pending_faults.count());
+        storage::Storage::stats_write(&mut flash, startup_stats);
+        pending_faults.clear();
+    }
 
-    let adc = Adc::new(peripherals.ADC, Irqs, Config::default());
+    let adc = tools::SharedAdc::new(Adc::new(peripherals.ADC, Irqs, Config::default()));
     let adc_p26 = Channel::new_pin(peripherals.PIN_26, Pull::None);
+    let adc_p27 = Channel::new_pin(peripherals.PIN_27, Pull::None);
+    let adc_p28 = Channel::new_pin(peripherals.PIN_28, Pull::None);
     let thermistor = thermistor::Thermistor::new_dyze500();
 
+    let spi1 = spi::Spi::new(
+        peripherals.SPI1,
+        peripherals.PIN_10,
+        peripherals.PIN_11,
+        peripherals.PIN_12,
+        peripherals.DMA_CH0,
+        peripherals.DMA_CH1,
+        spi::Config::default(),
+    );
+    let spi1_cs = Output::new(peripherals.PIN_13, Level::High);
+    let spi1_dev = embedded_hal_bus::spi::ExclusiveDevice::new(spi1, spi1_cs, embassy_time::Delay)
+        .expect("main: spi1 cs fail");
+
+    let plate_source = match startup_storage.plate_sensor {
+        source::SensorTypeEnum::Thermistor => {
+            source::Sensor::Thermistor(source::ThermistorSource::new(&adc, adc_p26, &thermistor))
+        }
+        source::SensorTypeEnum::Max31855 => source::Sensor::Thermocouple(
+            thermocouple::Thermocouple::new(spi1_dev, thermocouple::ThermocoupleChip::Max31855),
+        ),
+        source::SensorTypeEnum::Max6675 => source::Sensor::Thermocouple(
+            thermocouple::Thermocouple::new(spi1_dev, thermocouple::ThermocoupleChip::Max6675),
+        ),
+    };
+    let board_source = source::ThermistorSource::new(&adc, adc_p27, &thermistor);
+    let zone1_source =
+        source::Sensor::Thermistor(source::ThermistorSource::new(&adc, adc_p28, &thermistor));
+
+    let adc_temp = Channel::new_temp_sensor(peripherals.ADC_TEMP_SENSOR);
+    let adc_vsys = Channel::new_pin(peripherals.PIN_29, Pull::None);
+    let monitor = monitor::SystemMonitor::new(&adc, adc_temp, adc_vsys);
+
     let i2c0: i2c::I2c<'_, I2C0, i2c::Async> = i2c::I2c::new_async(
         peripherals.I2C0,
         peripherals.PIN_9,
@@ -75,9 +148,35 @@ async fn main_loop(_spawner: Spawner) -> ! {
     )
     .into_buffered_graphics_mode();
 
-    let mosfet = Pwm::new_output_a(
+    //zone slices need equal top and divider, counters are phased in heater::Heater::new
+    let zone0_pwm = Pwm::new_output_a(
         peripherals.PWM_SLICE3,
         peripherals.PIN_22,
+        zone::pwm_config(),
+    );
+    let fan_pwm = Pwm::new_output_a(
+        peripherals.PWM_SLICE2,
+        peripherals.PIN_20,
+        pwm::Config::default(),
+    );
+    let fan = fan::Fan::new(
+        fan_pwm,
+        startup_storage.fan_mode,
+        startup_storage.fan_cooling_rate,
+    );
+    let zone1_pwm = Pwm::new_output_a(
+        peripherals.PWM_SLICE7,
+        peripherals.PIN_14,
+        zone::pwm_config(),
+    );
+    //zone 0 is main plate, see zone::ZONES
+    let zones = [
+        zone::Zone::new(0, plate_source, zone0_pwm, &startup_storage),
+        zone::Zone::new(1, zone1_source, zone1_pwm, &startup_storage),
+    ];
+    let buzzer_pwm = Pwm::new_output_a(
+        peripherals.PWM_SLICE0,
+        peripherals.PIN_16,
         pwm::Config::default(),
     );
     let led = Output::new(peripherals.PIN_25, Level::Low);
@@ -85,6 +184,13 @@ async fn main_loop(_spawner: Spawner) -> ! {
     let btn1 = Input::new(peripherals.PIN_2, Pull::Up);
     let btn2 = Input::new(peripherals.PIN_3, Pull::Up);
     let btn3 = Input::new(peripherals.PIN_4, Pull::Up);
+    let interlocks = interlock::Interlocks::new(
+        [
+            Input::new(peripherals.PIN_5, Pull::Up),
+            Input::new(peripherals.PIN_6, Pull::Up),
+        ],
+        startup_storage.interlocks,
+    );
 
     let channels = channels::Channels::new();
 
@@ -93,21 +199,27 @@ async fn main_loop(_spawner: Spawner) -> ! {
     let mut display = display::Display::new(ssd1306_display, &channels);
     let mut heater = heater::Heater::new(
         &startup_storage,
-        adc,
-        adc_p26,
-        &thermistor,
-        mosfet,
+        startup_stats,
+        board_source,
+        monitor,
+        heater::HeaterIo {
+            fan,
+            zones,
+            interlocks,
+        },
         &channels,
     );
     let mut menu = menu::Menu::new(&startup_storage, btn1, btn2, btn3, &channels);
+    let mut buzzer = buzzer::Buzzer::new(buzzer_pwm, startup_storage.buzzer, &channels);
 
     let f1 = display.display_task();
     let f2 = heater.heat_task();
     let f3 = watchdog.wd_task();
     let f4 = menu.btn_task();
     let f5 = storage.flash_task();
+    let f6 = buzzer.buzzer_task();
 
-    let fut = join!(f1, f2, f3, f4, f5,);
+    let fut = join!(f1, f2, f3, f4, f5, f6,);
 
     fut.await;
     panic!("not reachable");
@@ -1,17 +1,33 @@
+use core::cmp::min;
 use core::fmt::Write;
 use embassy_rp::gpio::Input;
 use embassy_time::Timer;
 use simplestaticstring::{format_static, StaticString};
 
 use crate::{
+    allocator::AllocPolicyEnum,
+    autotune::{RelayResult, TuningRuleEnum, AUTOTUNE_CYCLES_MAX},
+    buzzer::SyncBuzzerStateEnum,
     channels,
     display::SyncDisplayStateEnum,
-    heater::SyncHeatStateEnum,
+    fan::FanModeEnum,
+    feedforward::{DutyMap, DUTY_MAP_POINTS, DUTY_MAP_TEMPS},
+    heater::{PostResultEnum, SyncHeatStateEnum},
+    interlock::{InterlockActionEnum, InterlockSettings, INTERLOCKS},
+    jitter::JitterReport,
+    output::OutputModeEnum,
+    plant::PlantModel,
+    schedule::{PidBand, PID_BANDS},
+    source::SensorTypeEnum,
+    stats::StatsData,
     storage::{self, SyncStorageStateEnum},
-    temperature::{self, TemperatureProfileEnum},
+    temperature::{self, LagCompensationEnum, SyncSourceEnum, TemperatureProfileEnum},
     tools::{SyncStateChannelReceiver, SyncStateChannelSender},
+    zone::{ZoneSettings, AUX_ZONES, ZONES},
 };
 
+const MENU_VISIBLE_ITEMS: usize = 4;
+
 //traits
 trait MenuItemTextTrait {
     fn get(&self, menu: &Menu) -> StaticString<20>;
@@ -73,6 +89,36 @@ impl MenuItemActionTrait for MenuItemTargetTempStatic {
     }
 }
 
+struct MenuItemTargetTempBake {}
+impl MenuItemTextTrait for MenuItemTargetTempBake {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("Bake temp: {:03}", menu.target_temp.0)
+    }
+}
+
+impl MenuItemActionTrait for MenuItemTargetTempBake {
+    fn call(&self, btn: u8, amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => {
+                menu.target_temp.0 = menu.target_temp.0.wrapping_add(amount as u16);
+                menu.target_temp.1 = true;
+                MenuItemAction::None
+            }
+            2 => {
+                menu.profile.0 = temperature::TemperatureProfileEnum::Bake;
+                menu.profile.1 = true;
+                MenuItemAction::Back
+            }
+            3 => {
+                menu.target_temp.0 = menu.target_temp.0.wrapping_sub(amount as u16);
+                menu.target_temp.1 = true;
+                MenuItemAction::None
+            }
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
 struct MenuItemTargetTempProfileA {}
 impl MenuItemTextTrait for MenuItemTargetTempProfileA {
     fn get(&self, menu: &Menu) -> StaticString<20> {
@@ -105,6 +151,36 @@ impl MenuItemActionTrait for MenuItemTargetTempProfileA {
     }
 }
 
+struct MenuItemTargetManual {}
+impl MenuItemTextTrait for MenuItemTargetManual {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("Manual power: {:03}%", menu.manual_duty)
+    }
+}
+
+impl MenuItemActionTrait for MenuItemTargetManual {
+    fn call(&self, btn: u8, amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => {
+                menu.manual_duty = min(menu.manual_duty.saturating_add(amount), 100);
+                MenuItemAction::None
+            }
+            2 => {
+                menu.profile.0 = temperature::TemperatureProfileEnum::Manual {
+                    duty: menu.manual_duty,
+                };
+                menu.profile.1 = true;
+                MenuItemAction::Back
+            }
+            3 => {
+                menu.manual_duty = menu.manual_duty.saturating_sub(amount);
+                MenuItemAction::None
+            }
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
 struct MenuItemPidP {}
 impl MenuItemTextTrait for MenuItemPidP {
     fn get(&self, menu: &Menu) -> StaticString<20> {
@@ -182,15 +258,77 @@ impl MenuItemActionTrait for MenuItemPidD {
         }
     }
 }
+struct MenuItemPidIMin {}
+impl MenuItemTextTrait for MenuItemPidIMin {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("Set I min: {:.2}", menu.pid_i_min.0)
+    }
+}
+
+impl MenuItemActionTrait for MenuItemPidIMin {
+    fn call(&self, btn: u8, amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => {
+                menu.pid_i_min.0 =
+                    (menu.pid_i_min.0 + 0.01 * (amount as f32)).min(menu.pid_i_max.0);
+                menu.pid_i_min.1 = true;
+                MenuItemAction::None
+            }
+            2 => MenuItemAction::Back,
+            3 => {
+                menu.pid_i_min.0 = (menu.pid_i_min.0 - 0.01 * (amount as f32)).max(-1.0);
+                menu.pid_i_min.1 = true;
+                MenuItemAction::None
+            }
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemPidIMax {}
+impl MenuItemTextTrait for MenuItemPidIMax {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("Set I max: {:.2}", menu.pid_i_max.0)
+    }
+}
+
+impl MenuItemActionTrait for MenuItemPidIMax {
+    fn call(&self, btn: u8, amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => {
+                menu.pid_i_max.0 = (menu.pid_i_max.0 + 0.01 * (amount as f32)).min(1.0);
+                menu.pid_i_max.1 = true;
+                MenuItemAction::None
+            }
+            2 => MenuItemAction::Back,
+            3 => {
+                menu.pid_i_max.0 =
+                    (menu.pid_i_max.0 - 0.01 * (amount as f32)).max(menu.pid_i_min.0);
+                menu.pid_i_max.1 = true;
+                MenuItemAction::None
+            }
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
 struct MenuItemPidAutoTune {}
 impl MenuItemTextTrait for MenuItemPidAutoTune {
     fn get(&self, menu: &Menu) -> StaticString<20> {
         match menu.pid_autotune_inprogress {
-            PidAutoTuneInProgressEnum::Idle => format_static!("Start"),
-            PidAutoTuneInProgressEnum::InProgress => {
-                format_static!("Abort [{}]", menu.pid_autotune_iteration)
+            PidAutoTuneInProgressEnum::Idle if menu.autotune_amplitude.0 > menu.max_duty.0 => {
+                format_static!("Amp > max duty")
             }
-            PidAutoTuneInProgressEnum::Done => format_static!("Close"),
+            PidAutoTuneInProgressEnum::Idle => format_static!("Start"),
+            PidAutoTuneInProgressEnum::InProgress => match menu.autotune_sweep {
+                true => format_static!(
+                    "Abort [B{} {}]",
+                    menu.autotune_band + 1,
+                    menu.pid_autotune_iteration
+                ),
+                false => format_static!("Abort [{}]", menu.pid_autotune_iteration),
+            },
+            PidAutoTuneInProgressEnum::Done => format_static!("Review"),
         }
     }
 }
@@ -198,12 +336,22 @@ impl MenuItemTextTrait for MenuItemPidAutoTune {
 impl MenuItemActionTrait for MenuItemPidAutoTune {
     fn call(&self, btn: u8, _amount: u8, menu: &mut Menu) -> MenuItemAction {
         match btn {
-            1 => MenuItemAction::None,
+            1 => MenuItemAction::MovePositionUp,
             2 => match menu.pid_autotune_inprogress {
+                //relay amplitude must be deliverable
+                PidAutoTuneInProgressEnum::Idle if menu.autotune_amplitude.0 > menu.max_duty.0 => {
+                    MenuItemAction::None
+                }
                 PidAutoTuneInProgressEnum::Idle => {
                     menu.pid_autotune_inprogress = PidAutoTuneInProgressEnum::InProgress;
+                    menu.pid_autotune_iteration = 0;
+                    menu.autotune_result = None;
+                    menu.autotune_results = [None; PID_BANDS];
+                    menu.autotune_band = 0;
+                    if menu.autotune_sweep {
+                        menu.target_temp = (menu.pid_bands.0[0].temp, true);
+                    }
 
-                    menu.pid = (false, true);
                     menu.profile = (
                         TemperatureProfileEnum::AutoCalibrate {
                             state: Default::default(),
@@ -216,18 +364,16 @@ impl MenuItemActionTrait for MenuItemPidAutoTune {
                 PidAutoTuneInProgressEnum::InProgress => {
                     menu.pid_autotune_inprogress = PidAutoTuneInProgressEnum::Idle;
 
-                    menu.pid = (false, true);
                     menu.profile = (TemperatureProfileEnum::Static, true);
                     menu.target_temp = (0, true);
 
                     MenuItemAction::Back
                 }
                 PidAutoTuneInProgressEnum::Done => {
-                    menu.pid_autotune_inprogress = PidAutoTuneInProgressEnum::Idle;
-                    MenuItemAction::Back
+                    MenuItemAction::OpenMenu(&MENU_PID_AUTOTUNE_REVIEW)
                 }
             },
-            3 => MenuItemAction::None,
+            3 => MenuItemAction::MovePositionDown,
             _ => MenuItemAction::None,
         }
     }
@@ -365,144 +511,2896 @@ impl MenuItemActionTrait for MenuItemTempLeadOffset {
     }
 }
 
-//menus
-const MENU_TOP: &MenuType = &[MenuItem {
-    text: MenuItemText::Static("Menu"),
-    action: MenuItemAction::OpenMenu(&MENU_MAIN),
-}];
+struct MenuItemPlateSensor {}
+impl MenuItemTextTrait for MenuItemPlateSensor {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!(
+            "Sensor: {}",
+            match menu.plate_sensor.0 {
+                SensorTypeEnum::Thermistor => "NTC",
+                SensorTypeEnum::Max31855 => "MAX31855",
+                SensorTypeEnum::Max6675 => "MAX6675",
+            }
+        )
+    }
+}
 
-const MENU_MAIN: &MenuType = &[
-    MenuItem {
-        text: MenuItemText::Static("Target temp"),
-        action: MenuItemAction::OpenMenu(&MENU_TARGET_TEMP),
-    },
-    MenuItem {
-        text: MenuItemText::Static("Pid"),
-        action: MenuItemAction::OpenMenu(&MENU_PID),
-    },
-    MenuItem {
-        text: MenuItemText::Static("Settings"),
-        action: MenuItemAction::OpenMenu(&MENU_SETTINGS),
-    },
-    MenuItem {
-        text: MenuItemText::Static("Back"),
-        action: MenuItemAction::Back,
-    },
-];
+impl MenuItemActionTrait for MenuItemPlateSensor {
+    fn call(&self, btn: u8, _amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => MenuItemAction::MovePositionUp,
+            2 => {
+                menu.plate_sensor.0 = match menu.plate_sensor.0 {
+                    SensorTypeEnum::Thermistor => SensorTypeEnum::Max31855,
+                    SensorTypeEnum::Max31855 => SensorTypeEnum::Max6675,
+                    SensorTypeEnum::Max6675 => SensorTypeEnum::Thermistor,
+                };
+                menu.plate_sensor.1 = true;
+                MenuItemAction::None
+            }
+            3 => MenuItemAction::MovePositionDown,
+            _ => MenuItemAction::None,
+        }
+    }
+}
 
-const MENU_TARGET_TEMP: &MenuType = &[
-    MenuItem {
-        text: MenuItemText::Static("Static target temp"),
-        action: MenuItemAction::OpenMenu(&MENU_TARGET_TEMP_STATIC),
-    },
-    MenuItem {
-        text: MenuItemText::Static("Temp profile A"),
-        action: MenuItemAction::OpenMenu(&MENU_TARGET_TEMP_PROFILE_A),
-    },
-    MenuItem {
-        text: MenuItemText::Static("Back"),
-        action: MenuItemAction::Back,
-    },
-];
+struct MenuItemBoardProbe {}
+impl MenuItemTextTrait for MenuItemBoardProbe {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!(
+            "Board probe: {}",
+            match menu.board_probe.0 {
+                true => "on",
+                false => "off",
+            }
+        )
+    }
+}
 
-const MENU_TARGET_TEMP_STATIC: &MenuType = &[MenuItem {
-    text: MenuItemText::Render(&MenuItemTargetTempStatic {}),
-    action: MenuItemAction::Custom(&MenuItemTargetTempStatic {}),
-}];
+impl MenuItemActionTrait for MenuItemBoardProbe {
+    fn call(&self, btn: u8, _amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => MenuItemAction::MovePositionUp,
+            2 => {
+                menu.board_probe.0 = !menu.board_probe.0;
+                menu.board_probe.1 = true;
+                MenuItemAction::None
+            }
+            3 => MenuItemAction::MovePositionDown,
+            _ => MenuItemAction::None,
+        }
+    }
+}
 
-const MENU_TARGET_TEMP_PROFILE_A: &MenuType = &[MenuItem {
-    text: MenuItemText::Render(&MenuItemTargetTempProfileA {}),
-    action: MenuItemAction::Custom(&MenuItemTargetTempProfileA {}),
-}];
+struct MenuItemLagCompensation {}
+impl MenuItemTextTrait for MenuItemLagCompensation {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!(
+            "Lag comp A: {}",
+            match menu.lag_profile_a.0 {
+                LagCompensationEnum::Fixed => "fixed",
+                LagCompensationEnum::Model => "model",
+            }
+        )
+    }
+}
 
-const MENU_PID: &MenuType = &[
-    MenuItem {
-        text: MenuItemText::Render(&MenuItemPidUsePid {}),
-        action: MenuItemAction::Custom(&MenuItemPidUsePid {}),
-    },
-    MenuItem {
-        text: MenuItemText::Static("Set manual"),
-        action: MenuItemAction::OpenMenu(&MENU_PID_MANUAL),
-    },
-    MenuItem {
-        text: MenuItemText::Static("AutoTune"),
-        action: MenuItemAction::OpenMenu(&MENU_PID_AUTOTUNE),
-    },
-    MenuItem {
-        text: MenuItemText::Static("Back"),
-        action: MenuItemAction::Back,
-    },
-];
+impl MenuItemActionTrait for MenuItemLagCompensation {
+    fn call(&self, btn: u8, _amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => MenuItemAction::MovePositionUp,
+            2 => {
+                menu.lag_profile_a.0 = match menu.lag_profile_a.0 {
+                    LagCompensationEnum::Fixed => LagCompensationEnum::Model,
+                    LagCompensationEnum::Model => LagCompensationEnum::Fixed,
+                };
+                menu.lag_profile_a.1 = true;
+                MenuItemAction::None
+            }
+            3 => MenuItemAction::MovePositionDown,
+            _ => MenuItemAction::None,
+        }
+    }
+}
 
-const MENU_PID_P: &MenuType = &[MenuItem {
-    text: MenuItemText::Render(&MenuItemPidP {}),
-    action: MenuItemAction::Custom(&MenuItemPidP {}),
-}];
+struct MenuItemSyncSource {}
+impl MenuItemTextTrait for MenuItemSyncSource {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!(
+            "Sync on: {}",
+            match menu.sync_source.0 {
+                SyncSourceEnum::Plate => "plate",
+                SyncSourceEnum::Board => "board",
+            }
+        )
+    }
+}
 
-const MENU_PID_I: &MenuType = &[MenuItem {
-    text: MenuItemText::Render(&MenuItemPidI {}),
-    action: MenuItemAction::Custom(&MenuItemPidI {}),
-}];
+impl MenuItemActionTrait for MenuItemSyncSource {
+    fn call(&self, btn: u8, _amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => MenuItemAction::MovePositionUp,
+            2 => {
+                menu.sync_source.0 = match menu.sync_source.0 {
+                    SyncSourceEnum::Plate => SyncSourceEnum::Board,
+                    SyncSourceEnum::Board => SyncSourceEnum::Plate,
+                };
+                menu.sync_source.1 = true;
+                MenuItemAction::None
+            }
+            3 => MenuItemAction::MovePositionDown,
+            _ => MenuItemAction::None,
+        }
+    }
+}
 
-const MENU_PID_D: &MenuType = &[MenuItem {
-    text: MenuItemText::Render(&MenuItemPidD {}),
-    action: MenuItemAction::Custom(&MenuItemPidD {}),
-}];
+struct MenuItemCascadeUse {}
+impl MenuItemTextTrait for MenuItemCascadeUse {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!(
+            "Use cascade: {}",
+            match menu.cascade.0 {
+                true => "true",
+                false => "false",
+            }
+        )
+    }
+}
 
-const MENU_PID_MANUAL: &MenuType = &[
-    MenuItem {
-        text: MenuItemText::Static("Set P"),
-        action: MenuItemAction::OpenMenu(&MENU_PID_P),
-    },
-    MenuItem {
-        text: MenuItemText::Static("Set I"),
-        action: MenuItemAction::OpenMenu(&MENU_PID_I),
-    },
-    MenuItem {
+impl MenuItemActionTrait for MenuItemCascadeUse {
+    fn call(&self, btn: u8, _amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => MenuItemAction::MovePositionUp,
+            2 => {
+                menu.cascade.0 = !menu.cascade.0;
+                menu.cascade.1 = true;
+                MenuItemAction::None
+            }
+            3 => MenuItemAction::MovePositionDown,
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemCascadeP {}
+impl MenuItemTextTrait for MenuItemCascadeP {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("Outer P: {:03.02}", menu.cascade_p.0)
+    }
+}
+
+impl MenuItemActionTrait for MenuItemCascadeP {
+    fn call(&self, btn: u8, amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => {
+                menu.cascade_p.0 += 0.01 * (amount as f32);
+                menu.cascade_p.1 = true;
+                MenuItemAction::None
+            }
+            2 => MenuItemAction::Back,
+            3 => {
+                menu.cascade_p.0 -= 0.01 * (amount as f32);
+                menu.cascade_p.1 = true;
+                MenuItemAction::None
+            }
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemCascadeI {}
+impl MenuItemTextTrait for MenuItemCascadeI {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("Outer I: {:03.03}", menu.cascade_i.0)
+    }
+}
+
+impl MenuItemActionTrait for MenuItemCascadeI {
+    fn call(&self, btn: u8, amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => {
+                menu.cascade_i.0 += 0.001 * (amount as f32);
+                menu.cascade_i.1 = true;
+                MenuItemAction::None
+            }
+            2 => MenuItemAction::Back,
+            3 => {
+                menu.cascade_i.0 -= 0.001 * (amount as f32);
+                menu.cascade_i.1 = true;
+                MenuItemAction::None
+            }
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemCascadeMaxOver {}
+impl MenuItemTextTrait for MenuItemCascadeMaxOver {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("Max over: {:03}", menu.cascade_max_over.0)
+    }
+}
+
+impl MenuItemActionTrait for MenuItemCascadeMaxOver {
+    fn call(&self, btn: u8, amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => {
+                menu.cascade_max_over.0 = menu.cascade_max_over.0.saturating_add(amount as u16);
+                menu.cascade_max_over.1 = true;
+                MenuItemAction::None
+            }
+            2 => MenuItemAction::Back,
+            3 => {
+                menu.cascade_max_over.0 = menu.cascade_max_over.0.saturating_sub(amount as u16);
+                menu.cascade_max_over.1 = true;
+                MenuItemAction::None
+            }
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemDiagDieTemp {}
+impl MenuItemTextTrait for MenuItemDiagDieTemp {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("MCU temp: {:.1}C", menu.die_temp)
+    }
+}
+
+struct MenuItemDiagVsys {}
+impl MenuItemTextTrait for MenuItemDiagVsys {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("VSYS: {:.2}V", menu.vsys)
+    }
+}
+
+struct MenuItemDiagJitter {}
+impl MenuItemTextTrait for MenuItemDiagJitter {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!(
+            "Jit: {:.1}/{:.1}ms",
+            menu.jitter.mean_us as f32 / 1000.0,
+            menu.jitter.max_us as f32 / 1000.0
+        )
+    }
+}
+
+struct MenuItemDiagOverruns {}
+impl MenuItemTextTrait for MenuItemDiagOverruns {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("Overruns: {}", menu.jitter.overruns)
+    }
+}
+
+struct MenuItemDiagPost {}
+impl MenuItemTextTrait for MenuItemDiagPost {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        match menu.post_result {
+            None => format_static!("POST: running"),
+            Some(PostResultEnum::Heated(rise)) => format_static!("POST: ok +{:.1}C", rise),
+            Some(PostResultEnum::Warm) => format_static!("POST: ok (warm)"),
+            Some(PostResultEnum::Skipped) => format_static!("POST: ok (no pulse)"),
+        }
+    }
+}
+
+struct MenuItemAutoTuneRule {}
+impl MenuItemTextTrait for MenuItemAutoTuneRule {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("Rule: {}", menu.autotune_rule.0.name())
+    }
+}
+
+impl MenuItemActionTrait for MenuItemAutoTuneRule {
+    fn call(&self, btn: u8, _amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => MenuItemAction::MovePositionUp,
+            2 => {
+                menu.autotune_rule.0 = menu.autotune_rule.0.next();
+                menu.autotune_rule.1 = true;
+                MenuItemAction::None
+            }
+            3 => MenuItemAction::MovePositionDown,
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemAutoTuneAmplitude {}
+impl MenuItemTextTrait for MenuItemAutoTuneAmplitude {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("Relay amp: {:.2}", menu.autotune_amplitude.0)
+    }
+}
+
+impl MenuItemActionTrait for MenuItemAutoTuneAmplitude {
+    fn call(&self, btn: u8, amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => {
+                menu.autotune_amplitude.0 =
+                    (menu.autotune_amplitude.0 + 0.01 * (amount as f32)).min(1.0);
+                menu.autotune_amplitude.1 = true;
+                MenuItemAction::None
+            }
+            2 => MenuItemAction::Back,
+            3 => {
+                menu.autotune_amplitude.0 =
+                    (menu.autotune_amplitude.0 - 0.01 * (amount as f32)).max(0.05);
+                menu.autotune_amplitude.1 = true;
+                MenuItemAction::None
+            }
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemAutoTuneHysteresis {}
+impl MenuItemTextTrait for MenuItemAutoTuneHysteresis {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("Hysteresis: {:.1}", menu.autotune_hysteresis.0)
+    }
+}
+
+impl MenuItemActionTrait for MenuItemAutoTuneHysteresis {
+    fn call(&self, btn: u8, amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => {
+                menu.autotune_hysteresis.0 += 0.1 * (amount as f32);
+                menu.autotune_hysteresis.1 = true;
+                MenuItemAction::None
+            }
+            2 => MenuItemAction::Back,
+            3 => {
+                menu.autotune_hysteresis.0 =
+                    (menu.autotune_hysteresis.0 - 0.1 * (amount as f32)).max(0.0);
+                menu.autotune_hysteresis.1 = true;
+                MenuItemAction::None
+            }
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemAutoTuneCycles {}
+impl MenuItemTextTrait for MenuItemAutoTuneCycles {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("Cycles: {:02}", menu.autotune_cycles.0)
+    }
+}
+
+impl MenuItemActionTrait for MenuItemAutoTuneCycles {
+    fn call(&self, btn: u8, amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => {
+                menu.autotune_cycles.0 = menu
+                    .autotune_cycles
+                    .0
+                    .saturating_add(amount)
+                    .min(AUTOTUNE_CYCLES_MAX as u8);
+                menu.autotune_cycles.1 = true;
+                MenuItemAction::None
+            }
+            2 => MenuItemAction::Back,
+            3 => {
+                menu.autotune_cycles.0 = menu.autotune_cycles.0.saturating_sub(amount).max(1);
+                menu.autotune_cycles.1 = true;
+                MenuItemAction::None
+            }
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemAutoTuneP {}
+impl MenuItemTextTrait for MenuItemAutoTuneP {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        match menu.autotune_compare {
+            true => format_static!("Cur P: {:03.02}", menu.autotune_stored_gains().0),
+            false => format_static!("New P: {:03.02}", menu.autotune_gains().0),
+        }
+    }
+}
+
+struct MenuItemAutoTuneI {}
+impl MenuItemTextTrait for MenuItemAutoTuneI {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        match menu.autotune_compare {
+            true => format_static!("Cur I: {:03.02}", menu.autotune_stored_gains().1),
+            false => format_static!("New I: {:03.02}", menu.autotune_gains().1),
+        }
+    }
+}
+
+struct MenuItemAutoTuneD {}
+impl MenuItemTextTrait for MenuItemAutoTuneD {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        match menu.autotune_compare {
+            true => format_static!("Cur D: {:03.02}", menu.autotune_stored_gains().2),
+            false => format_static!("New D: {:03.02}", menu.autotune_gains().2),
+        }
+    }
+}
+
+struct MenuItemAutoTuneOscillation {}
+impl MenuItemTextTrait for MenuItemAutoTuneOscillation {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        let result = menu.autotune_result.unwrap_or_default();
+        format_static!("A:{:.1}C Tu:{:.1}s", result.amplitude, result.tu)
+    }
+}
+
+struct MenuItemAutoTuneQuality {}
+impl MenuItemTextTrait for MenuItemAutoTuneQuality {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        let result = menu.autotune_result.unwrap_or_default();
+        format_static!("Quality: {:03}%", (result.quality * 100.0) as u8)
+    }
+}
+
+struct MenuItemAutoTuneCompare {}
+impl MenuItemTextTrait for MenuItemAutoTuneCompare {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!(
+            "Show: {}",
+            match menu.autotune_compare {
+                true => "stored",
+                false => "new",
+            }
+        )
+    }
+}
+
+impl MenuItemActionTrait for MenuItemAutoTuneCompare {
+    fn call(&self, btn: u8, _amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => MenuItemAction::MovePositionUp,
+            2 => {
+                menu.autotune_compare = !menu.autotune_compare;
+                MenuItemAction::None
+            }
+            3 => MenuItemAction::MovePositionDown,
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemAutoTuneAccept {}
+impl MenuItemTextTrait for MenuItemAutoTuneAccept {
+    fn get(&self, _menu: &Menu) -> StaticString<20> {
+        format_static!("Accept")
+    }
+}
+
+impl MenuItemActionTrait for MenuItemAutoTuneAccept {
+    fn call(&self, btn: u8, _amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => MenuItemAction::MovePositionUp,
+            2 => {
+                if menu.autotune_sweep {
+                    for (band, result) in menu.autotune_results.iter().enumerate() {
+                        if let Some(result) = result {
+                            let (pid_p, pid_i, pid_d) = menu.rule_gains(result);
+                            let band = &mut menu.pid_bands.0[band];
+                            band.p = pid_p;
+                            band.i = pid_i;
+                            band.d = pid_d;
+                            band.enabled = true;
+                        }
+                    }
+                    menu.pid_bands.1 = true;
+                    menu.pid_schedule = (true, true);
+                } else {
+                    let (pid_p, pid_i, pid_d) = menu.autotune_gains();
+                    menu.pid_p = (pid_p, true);
+                    menu.pid_i = (pid_i, true);
+                    menu.pid_d = (pid_d, true);
+                }
+                menu.pid = (true, true);
+                menu.autotune_result = None;
+                menu.pid_autotune_inprogress = PidAutoTuneInProgressEnum::Idle;
+                MenuItemAction::Back
+            }
+            3 => MenuItemAction::MovePositionDown,
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemAutoTuneReject {}
+impl MenuItemTextTrait for MenuItemAutoTuneReject {
+    fn get(&self, _menu: &Menu) -> StaticString<20> {
+        format_static!("Reject")
+    }
+}
+
+impl MenuItemActionTrait for MenuItemAutoTuneReject {
+    fn call(&self, btn: u8, _amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => MenuItemAction::MovePositionUp,
+            2 => {
+                menu.autotune_result = None;
+                menu.pid_autotune_inprogress = PidAutoTuneInProgressEnum::Idle;
+                MenuItemAction::Back
+            }
+            3 => MenuItemAction::MovePositionDown,
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemAutoTuneSweep {}
+impl MenuItemTextTrait for MenuItemAutoTuneSweep {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!(
+            "Tune: {}",
+            match menu.autotune_sweep {
+                true => "bands",
+                false => "target",
+            }
+        )
+    }
+}
+
+impl MenuItemActionTrait for MenuItemAutoTuneSweep {
+    fn call(&self, btn: u8, _amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => MenuItemAction::MovePositionUp,
+            2 => {
+                if let PidAutoTuneInProgressEnum::Idle = menu.pid_autotune_inprogress {
+                    menu.autotune_sweep = !menu.autotune_sweep;
+                }
+                MenuItemAction::None
+            }
+            3 => MenuItemAction::MovePositionDown,
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemAutoTuneBand {}
+impl MenuItemTextTrait for MenuItemAutoTuneBand {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        match menu.autotune_sweep {
+            true => format_static!(
+                "Band {}: {:03}C",
+                menu.autotune_band + 1,
+                menu.pid_bands.0[menu.autotune_band].temp
+            ),
+            false => format_static!("Band: base"),
+        }
+    }
+}
+
+impl MenuItemActionTrait for MenuItemAutoTuneBand {
+    fn call(&self, btn: u8, _amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => MenuItemAction::MovePositionUp,
+            2 => {
+                if menu.autotune_sweep {
+                    menu.autotune_band = (menu.autotune_band + 1) % PID_BANDS;
+                    menu.autotune_result = menu.autotune_results[menu.autotune_band];
+                }
+                MenuItemAction::None
+            }
+            3 => MenuItemAction::MovePositionDown,
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemPidScheduleUse {}
+impl MenuItemTextTrait for MenuItemPidScheduleUse {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!(
+            "Use schedule: {}",
+            match menu.pid_schedule.0 {
+                true => "true",
+                false => "false",
+            }
+        )
+    }
+}
+
+impl MenuItemActionTrait for MenuItemPidScheduleUse {
+    fn call(&self, btn: u8, _amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => MenuItemAction::MovePositionUp,
+            2 => {
+                menu.pid_schedule.0 = !menu.pid_schedule.0;
+                menu.pid_schedule.1 = true;
+                MenuItemAction::None
+            }
+            3 => MenuItemAction::MovePositionDown,
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemPidBandSelect {}
+impl MenuItemTextTrait for MenuItemPidBandSelect {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        let band = &menu.pid_bands.0[menu.pid_band_edit];
+        format_static!(
+            "Band {}: {:03}C {}",
+            menu.pid_band_edit + 1,
+            band.temp,
+            match band.enabled {
+                true => "on",
+                false => "off",
+            }
+        )
+    }
+}
+
+impl MenuItemActionTrait for MenuItemPidBandSelect {
+    fn call(&self, btn: u8, _amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => MenuItemAction::MovePositionUp,
+            2 => {
+                menu.pid_band_edit = (menu.pid_band_edit + 1) % PID_BANDS;
+                MenuItemAction::None
+            }
+            3 => MenuItemAction::MovePositionDown,
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemPidBandEnabled {}
+impl MenuItemTextTrait for MenuItemPidBandEnabled {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!(
+            "Band enabled: {}",
+            match menu.pid_bands.0[menu.pid_band_edit].enabled {
+                true => "true",
+                false => "false",
+            }
+        )
+    }
+}
+
+impl MenuItemActionTrait for MenuItemPidBandEnabled {
+    fn call(&self, btn: u8, _amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => MenuItemAction::MovePositionUp,
+            2 => {
+                let band = &mut menu.pid_bands.0[menu.pid_band_edit];
+                band.enabled = !band.enabled;
+                menu.pid_bands.1 = true;
+                MenuItemAction::None
+            }
+            3 => MenuItemAction::MovePositionDown,
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemPidBandTemp {}
+impl MenuItemTextTrait for MenuItemPidBandTemp {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!(
+            "Band temp: {:03}",
+            menu.pid_bands.0[menu.pid_band_edit].temp
+        )
+    }
+}
+
+impl MenuItemActionTrait for MenuItemPidBandTemp {
+    fn call(&self, btn: u8, amount: u8, menu: &mut Menu) -> MenuItemAction {
+        let band = &mut menu.pid_bands.0[menu.pid_band_edit];
+        match btn {
+            1 => {
+                band.temp = band.temp.wrapping_add(amount as u16);
+                menu.pid_bands.1 = true;
+                MenuItemAction::None
+            }
+            2 => MenuItemAction::Back,
+            3 => {
+                band.temp = band.temp.wrapping_sub(amount as u16);
+                menu.pid_bands.1 = true;
+                MenuItemAction::None
+            }
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemPidBandP {}
+impl MenuItemTextTrait for MenuItemPidBandP {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("Band P: {:03.02}", menu.pid_bands.0[menu.pid_band_edit].p)
+    }
+}
+
+impl MenuItemActionTrait for MenuItemPidBandP {
+    fn call(&self, btn: u8, amount: u8, menu: &mut Menu) -> MenuItemAction {
+        let band = &mut menu.pid_bands.0[menu.pid_band_edit];
+        match btn {
+            1 => {
+                band.p += 0.01 * (amount as f32);
+                menu.pid_bands.1 = true;
+                MenuItemAction::None
+            }
+            2 => MenuItemAction::Back,
+            3 => {
+                band.p -= 0.01 * (amount as f32);
+                menu.pid_bands.1 = true;
+                MenuItemAction::None
+            }
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemPidBandI {}
+impl MenuItemTextTrait for MenuItemPidBandI {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("Band I: {:03.02}", menu.pid_bands.0[menu.pid_band_edit].i)
+    }
+}
+
+impl MenuItemActionTrait for MenuItemPidBandI {
+    fn call(&self, btn: u8, amount: u8, menu: &mut Menu) -> MenuItemAction {
+        let band = &mut menu.pid_bands.0[menu.pid_band_edit];
+        match btn {
+            1 => {
+                band.i += 0.1 * (amount as f32);
+                menu.pid_bands.1 = true;
+                MenuItemAction::None
+            }
+            2 => MenuItemAction::Back,
+            3 => {
+                band.i -= 0.1 * (amount as f32);
+                menu.pid_bands.1 = true;
+                MenuItemAction::None
+            }
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemPidBandD {}
+impl MenuItemTextTrait for MenuItemPidBandD {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("Band D: {:03.02}", menu.pid_bands.0[menu.pid_band_edit].d)
+    }
+}
+
+impl MenuItemActionTrait for MenuItemPidBandD {
+    fn call(&self, btn: u8, amount: u8, menu: &mut Menu) -> MenuItemAction {
+        let band = &mut menu.pid_bands.0[menu.pid_band_edit];
+        match btn {
+            1 => {
+                band.d += 0.1 * (amount as f32);
+                menu.pid_bands.1 = true;
+                MenuItemAction::None
+            }
+            2 => MenuItemAction::Back,
+            3 => {
+                band.d -= 0.1 * (amount as f32);
+                menu.pid_bands.1 = true;
+                MenuItemAction::None
+            }
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemCharacterize {}
+impl MenuItemTextTrait for MenuItemCharacterize {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        match menu.characterize_inprogress {
+            PidAutoTuneInProgressEnum::Idle if menu.characterize_duty.0 > menu.max_duty.0 => {
+                format_static!("Duty > max duty")
+            }
+            PidAutoTuneInProgressEnum::Idle => {
+                format_static!("Start [max {:03}C]", menu.target_temp.0)
+            }
+            PidAutoTuneInProgressEnum::InProgress => {
+                format_static!("Abort [{}s]", menu.characterize_elapsed)
+            }
+            PidAutoTuneInProgressEnum::Done => match menu.characterize_fit {
+                true => format_static!("Done: model ok"),
+                false => format_static!("Done: fit failed"),
+            },
+        }
+    }
+}
+
+impl MenuItemActionTrait for MenuItemCharacterize {
+    fn call(&self, btn: u8, _amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => MenuItemAction::MovePositionUp,
+            2 => match menu.characterize_inprogress {
+                //step duty must be deliverable
+                PidAutoTuneInProgressEnum::Idle if menu.characterize_duty.0 > menu.max_duty.0 => {
+                    MenuItemAction::None
+                }
+                PidAutoTuneInProgressEnum::Idle => {
+                    menu.characterize_inprogress = PidAutoTuneInProgressEnum::InProgress;
+                    menu.characterize_elapsed = 0;
+
+                    menu.profile = (
+                        TemperatureProfileEnum::Characterize {
+                            state: Default::default(),
+                        },
+                        true,
+                    );
+
+                    MenuItemAction::None
+                }
+                PidAutoTuneInProgressEnum::InProgress => {
+                    menu.characterize_inprogress = PidAutoTuneInProgressEnum::Idle;
+
+                    menu.profile = (TemperatureProfileEnum::Static, true);
+                    menu.target_temp = (0, true);
+
+                    MenuItemAction::Back
+                }
+                PidAutoTuneInProgressEnum::Done => {
+                    menu.characterize_inprogress = PidAutoTuneInProgressEnum::Idle;
+                    MenuItemAction::None
+                }
+            },
+            3 => MenuItemAction::MovePositionDown,
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemCharacterizeDuty {}
+impl MenuItemTextTrait for MenuItemCharacterizeDuty {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!(
+            "Step duty: {:03}%",
+            (menu.characterize_duty.0 * 100.0) as u8
+        )
+    }
+}
+
+impl MenuItemActionTrait for MenuItemCharacterizeDuty {
+    fn call(&self, btn: u8, amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => {
+                menu.characterize_duty.0 =
+                    (menu.characterize_duty.0 + 0.01 * (amount as f32)).min(1.0);
+                menu.characterize_duty.1 = true;
+                MenuItemAction::None
+            }
+            2 => MenuItemAction::Back,
+            3 => {
+                menu.characterize_duty.0 =
+                    (menu.characterize_duty.0 - 0.01 * (amount as f32)).max(0.01);
+                menu.characterize_duty.1 = true;
+                MenuItemAction::None
+            }
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemImcLambda {}
+impl MenuItemTextTrait for MenuItemImcLambda {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("Lambda: {:.1} tau", menu.imc_lambda.0)
+    }
+}
+
+impl MenuItemActionTrait for MenuItemImcLambda {
+    fn call(&self, btn: u8, amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => {
+                menu.imc_lambda.0 += 0.1 * (amount as f32);
+                menu.imc_lambda.1 = true;
+                MenuItemAction::None
+            }
+            2 => MenuItemAction::Back,
+            3 => {
+                menu.imc_lambda.0 = (menu.imc_lambda.0 - 0.1 * (amount as f32)).max(0.1);
+                menu.imc_lambda.1 = true;
+                MenuItemAction::None
+            }
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemPlantModel {}
+impl MenuItemTextTrait for MenuItemPlantModel {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        let model = &menu.plant_model.0;
+        format_static!(
+            "K:{:.2} T:{:.0} L:{:.0}",
+            model.gain,
+            model.tau,
+            model.dead_time
+        )
+    }
+}
+
+struct MenuItemImcP {}
+impl MenuItemTextTrait for MenuItemImcP {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("Sug P: {:03.02}", menu.imc_gains().0)
+    }
+}
+
+struct MenuItemImcI {}
+impl MenuItemTextTrait for MenuItemImcI {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("Sug I: {:03.02}", menu.imc_gains().1)
+    }
+}
+
+struct MenuItemImcD {}
+impl MenuItemTextTrait for MenuItemImcD {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("Sug D: {:03.02}", menu.imc_gains().2)
+    }
+}
+
+struct MenuItemImcApply {}
+impl MenuItemTextTrait for MenuItemImcApply {
+    fn get(&self, _menu: &Menu) -> StaticString<20> {
+        format_static!("Apply gains")
+    }
+}
+
+impl MenuItemActionTrait for MenuItemImcApply {
+    fn call(&self, btn: u8, _amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => MenuItemAction::MovePositionUp,
+            2 => {
+                if !menu.plant_model.0.valid() {
+                    return MenuItemAction::None;
+                }
+                let (pid_p, pid_i, pid_d) = menu.imc_gains();
+                menu.pid = (true, true);
+                menu.pid_p = (pid_p, true);
+                menu.pid_i = (pid_i, true);
+                menu.pid_d = (pid_d, true);
+                MenuItemAction::Back
+            }
+            3 => MenuItemAction::MovePositionDown,
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemFeedForwardUse {}
+impl MenuItemTextTrait for MenuItemFeedForwardUse {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!(
+            "Use FF: {}",
+            match menu.feedforward.0 {
+                true => "true",
+                false => "false",
+            }
+        )
+    }
+}
+
+impl MenuItemActionTrait for MenuItemFeedForwardUse {
+    fn call(&self, btn: u8, _amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => MenuItemAction::MovePositionUp,
+            2 => {
+                menu.feedforward.0 = !menu.feedforward.0;
+                menu.feedforward.1 = true;
+                MenuItemAction::None
+            }
+            3 => MenuItemAction::MovePositionDown,
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemFeedForwardLearn {}
+impl MenuItemTextTrait for MenuItemFeedForwardLearn {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!(
+            "Learn holds: {}",
+            match menu.feedforward_learn.0 {
+                true => "true",
+                false => "false",
+            }
+        )
+    }
+}
+
+impl MenuItemActionTrait for MenuItemFeedForwardLearn {
+    fn call(&self, btn: u8, _amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => MenuItemAction::MovePositionUp,
+            2 => {
+                menu.feedforward_learn.0 = !menu.feedforward_learn.0;
+                menu.feedforward_learn.1 = true;
+                MenuItemAction::None
+            }
+            3 => MenuItemAction::MovePositionDown,
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemDutyMap {
+    first: usize,
+}
+impl MenuItemTextTrait for MenuItemDutyMap {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        let mut output = StaticString::default();
+        for point in self.first..min(self.first + 2, DUTY_MAP_POINTS) {
+            let text: StaticString<10> = match menu.duty_map.0[point] {
+                Some(duty) => {
+                    format_static!("{:03}:{:02}% ", DUTY_MAP_TEMPS[point], (duty * 100.0) as u8)
+                }
+                None => format_static!("{:03}:--% ", DUTY_MAP_TEMPS[point]),
+            };
+            if output.try_extend_from_slice(text.as_bytes()).is_err() {
+                break;
+            }
+        }
+        output
+    }
+}
+
+struct MenuItemDutyMapSeed {}
+impl MenuItemTextTrait for MenuItemDutyMapSeed {
+    fn get(&self, _menu: &Menu) -> StaticString<20> {
+        format_static!("Seed from model")
+    }
+}
+
+impl MenuItemActionTrait for MenuItemDutyMapSeed {
+    fn call(&self, btn: u8, _amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => MenuItemAction::MovePositionUp,
+            2 => {
+                let model = &menu.plant_model.0;
+                if model.valid() {
+                    for (duty, temp) in menu.duty_map.0.iter_mut().zip(DUTY_MAP_TEMPS) {
+                        let steady = (temp as f32 - model.ambient) / (model.gain * 100.0);
+                        *duty = Some(steady.clamp(0.0, 1.0));
+                    }
+                    menu.duty_map.1 = true;
+                }
+                MenuItemAction::None
+            }
+            3 => MenuItemAction::MovePositionDown,
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemDutyMapClear {}
+impl MenuItemTextTrait for MenuItemDutyMapClear {
+    fn get(&self, _menu: &Menu) -> StaticString<20> {
+        format_static!("Clear map")
+    }
+}
+
+impl MenuItemActionTrait for MenuItemDutyMapClear {
+    fn call(&self, btn: u8, _amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => MenuItemAction::MovePositionUp,
+            2 => {
+                menu.duty_map = ([None; DUTY_MAP_POINTS], true);
+                MenuItemAction::None
+            }
+            3 => MenuItemAction::MovePositionDown,
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemOutputMode {}
+impl MenuItemTextTrait for MenuItemOutputMode {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!(
+            "Output: {}",
+            match menu.output_mode.0 {
+                OutputModeEnum::Pwm => "PWM",
+                OutputModeEnum::TimeProportional => "SSR",
+            }
+        )
+    }
+}
+
+impl MenuItemActionTrait for MenuItemOutputMode {
+    fn call(&self, btn: u8, _amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => MenuItemAction::MovePositionUp,
+            2 => {
+                menu.output_mode.0 = match menu.output_mode.0 {
+                    OutputModeEnum::Pwm => OutputModeEnum::TimeProportional,
+                    OutputModeEnum::TimeProportional => OutputModeEnum::Pwm,
+                };
+                menu.output_mode.1 = true;
+                MenuItemAction::None
+            }
+            3 => MenuItemAction::MovePositionDown,
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemOutputWindow {}
+impl MenuItemTextTrait for MenuItemOutputWindow {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("Window: {:.1}s", menu.output_window.0)
+    }
+}
+
+impl MenuItemActionTrait for MenuItemOutputWindow {
+    fn call(&self, btn: u8, amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => {
+                menu.output_window.0 = (menu.output_window.0 + 0.1 * (amount as f32)).min(10.0);
+                menu.output_window.1 = true;
+                MenuItemAction::None
+            }
+            2 => MenuItemAction::Back,
+            3 => {
+                menu.output_window.0 = (menu.output_window.0 - 0.1 * (amount as f32))
+                    .max(0.5)
+                    .max(menu.output_min_on.0 + menu.output_min_off.0);
+                menu.output_window.1 = true;
+                MenuItemAction::None
+            }
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemOutputMinOn {}
+impl MenuItemTextTrait for MenuItemOutputMinOn {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("Min on: {:.1}s", menu.output_min_on.0)
+    }
+}
+
+impl MenuItemActionTrait for MenuItemOutputMinOn {
+    fn call(&self, btn: u8, amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => {
+                menu.output_min_on.0 = (menu.output_min_on.0 + 0.1 * (amount as f32))
+                    .min(menu.output_window.0 - menu.output_min_off.0);
+                menu.output_min_on.1 = true;
+                MenuItemAction::None
+            }
+            2 => MenuItemAction::Back,
+            3 => {
+                menu.output_min_on.0 = (menu.output_min_on.0 - 0.1 * (amount as f32)).max(0.0);
+                menu.output_min_on.1 = true;
+                MenuItemAction::None
+            }
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemOutputMinOff {}
+impl MenuItemTextTrait for MenuItemOutputMinOff {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("Min off: {:.1}s", menu.output_min_off.0)
+    }
+}
+
+impl MenuItemActionTrait for MenuItemOutputMinOff {
+    fn call(&self, btn: u8, amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => {
+                menu.output_min_off.0 = (menu.output_min_off.0 + 0.1 * (amount as f32))
+                    .min(menu.output_window.0 - menu.output_min_on.0);
+                menu.output_min_off.1 = true;
+                MenuItemAction::None
+            }
+            2 => MenuItemAction::Back,
+            3 => {
+                menu.output_min_off.0 = (menu.output_min_off.0 - 0.1 * (amount as f32)).max(0.0);
+                menu.output_min_off.1 = true;
+                MenuItemAction::None
+            }
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemMaxDuty {}
+impl MenuItemTextTrait for MenuItemMaxDuty {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("Max duty: {:03}%", (menu.max_duty.0 * 100.0) as u8)
+    }
+}
+
+impl MenuItemActionTrait for MenuItemMaxDuty {
+    fn call(&self, btn: u8, amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => {
+                menu.max_duty.0 = (menu.max_duty.0 + 0.01 * (amount as f32)).min(1.0);
+                menu.max_duty.1 = true;
+                MenuItemAction::None
+            }
+            2 => MenuItemAction::Back,
+            3 => {
+                menu.max_duty.0 = (menu.max_duty.0 - 0.01 * (amount as f32)).max(0.1);
+                menu.max_duty.1 = true;
+                MenuItemAction::None
+            }
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemMaxDutySoak {}
+impl MenuItemTextTrait for MenuItemMaxDutySoak {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("Soak max: {:03}%", (menu.max_duty_soak.0 * 100.0) as u8)
+    }
+}
+
+impl MenuItemActionTrait for MenuItemMaxDutySoak {
+    fn call(&self, btn: u8, amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => {
+                menu.max_duty_soak.0 = (menu.max_duty_soak.0 + 0.01 * (amount as f32)).min(1.0);
+                menu.max_duty_soak.1 = true;
+                MenuItemAction::None
+            }
+            2 => MenuItemAction::Back,
+            3 => {
+                menu.max_duty_soak.0 = (menu.max_duty_soak.0 - 0.01 * (amount as f32)).max(0.1);
+                menu.max_duty_soak.1 = true;
+                MenuItemAction::None
+            }
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemSoftStart {}
+impl MenuItemTextTrait for MenuItemSoftStart {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("Soft start: {:03}s", menu.soft_start.0 as u16)
+    }
+}
+
+impl MenuItemActionTrait for MenuItemSoftStart {
+    fn call(&self, btn: u8, amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => {
+                menu.soft_start.0 = (menu.soft_start.0 + 1.0 * (amount as f32)).min(120.0);
+                menu.soft_start.1 = true;
+                MenuItemAction::None
+            }
+            2 => MenuItemAction::Back,
+            3 => {
+                menu.soft_start.0 = (menu.soft_start.0 - 1.0 * (amount as f32)).max(0.0);
+                menu.soft_start.1 = true;
+                MenuItemAction::None
+            }
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemBangBelow {}
+impl MenuItemTextTrait for MenuItemBangBelow {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("Hyst below: {:.1}", menu.bang_below.0)
+    }
+}
+
+impl MenuItemActionTrait for MenuItemBangBelow {
+    fn call(&self, btn: u8, amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => {
+                menu.bang_below.0 = (menu.bang_below.0 + 0.1 * (amount as f32)).min(20.0);
+                menu.bang_below.1 = true;
+                MenuItemAction::None
+            }
+            2 => MenuItemAction::Back,
+            3 => {
+                menu.bang_below.0 = (menu.bang_below.0 - 0.1 * (amount as f32)).max(0.0);
+                menu.bang_below.1 = true;
+                MenuItemAction::None
+            }
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemBangAbove {}
+impl MenuItemTextTrait for MenuItemBangAbove {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("Hyst above: {:.1}", menu.bang_above.0)
+    }
+}
+
+impl MenuItemActionTrait for MenuItemBangAbove {
+    fn call(&self, btn: u8, amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => {
+                menu.bang_above.0 = (menu.bang_above.0 + 0.1 * (amount as f32)).min(20.0);
+                menu.bang_above.1 = true;
+                MenuItemAction::None
+            }
+            2 => MenuItemAction::Back,
+            3 => {
+                menu.bang_above.0 = (menu.bang_above.0 - 0.1 * (amount as f32)).max(0.0);
+                menu.bang_above.1 = true;
+                MenuItemAction::None
+            }
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemBangMinSwitch {}
+impl MenuItemTextTrait for MenuItemBangMinSwitch {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("Min switch: {:.1}s", menu.bang_min_switch.0)
+    }
+}
+
+impl MenuItemActionTrait for MenuItemBangMinSwitch {
+    fn call(&self, btn: u8, amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => {
+                menu.bang_min_switch.0 = (menu.bang_min_switch.0 + 0.1 * (amount as f32)).min(30.0);
+                menu.bang_min_switch.1 = true;
+                MenuItemAction::None
+            }
+            2 => MenuItemAction::Back,
+            3 => {
+                menu.bang_min_switch.0 = (menu.bang_min_switch.0 - 0.1 * (amount as f32)).max(0.0);
+                menu.bang_min_switch.1 = true;
+                MenuItemAction::None
+            }
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemControlPeriod {}
+impl MenuItemTextTrait for MenuItemControlPeriod {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("Loop period: {:03}ms", menu.control_period.0)
+    }
+}
+
+impl MenuItemActionTrait for MenuItemControlPeriod {
+    fn call(&self, btn: u8, amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => {
+                menu.control_period.0 = min(menu.control_period.0 + 10 * amount as u16, 500);
+                menu.control_period.1 = true;
+                MenuItemAction::None
+            }
+            2 => MenuItemAction::Back,
+            3 => {
+                menu.control_period.0 = menu
+                    .control_period
+                    .0
+                    .saturating_sub(10 * amount as u16)
+                    .max(20);
+                menu.control_period.1 = true;
+                MenuItemAction::None
+            }
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemFanMode {}
+impl MenuItemTextTrait for MenuItemFanMode {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!(
+            "Fan: {}",
+            match menu.fan_mode.0 {
+                FanModeEnum::Auto => "auto",
+                FanModeEnum::On => "on",
+                FanModeEnum::Off => "off",
+            }
+        )
+    }
+}
+
+impl MenuItemActionTrait for MenuItemFanMode {
+    fn call(&self, btn: u8, _amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => MenuItemAction::MovePositionUp,
+            2 => {
+                menu.fan_mode.0 = match menu.fan_mode.0 {
+                    FanModeEnum::Auto => FanModeEnum::On,
+                    FanModeEnum::On => FanModeEnum::Off,
+                    FanModeEnum::Off => FanModeEnum::Auto,
+                };
+                menu.fan_mode.1 = true;
+                MenuItemAction::None
+            }
+            3 => MenuItemAction::MovePositionDown,
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemFanCoolingRate {}
+impl MenuItemTextTrait for MenuItemFanCoolingRate {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("Cool rate: {:.1}C/s", menu.fan_cooling_rate.0)
+    }
+}
+
+impl MenuItemActionTrait for MenuItemFanCoolingRate {
+    fn call(&self, btn: u8, amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => {
+                menu.fan_cooling_rate.0 =
+                    (menu.fan_cooling_rate.0 + 0.1 * (amount as f32)).min(10.0);
+                menu.fan_cooling_rate.1 = true;
+                MenuItemAction::None
+            }
+            2 => MenuItemAction::Back,
+            3 => {
+                menu.fan_cooling_rate.0 =
+                    (menu.fan_cooling_rate.0 - 0.1 * (amount as f32)).max(0.1);
+                menu.fan_cooling_rate.1 = true;
+                MenuItemAction::None
+            }
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemBuzzer {}
+impl MenuItemTextTrait for MenuItemBuzzer {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!(
+            "Buzzer: {}",
+            match menu.buzzer.0 {
+                true => "on",
+                false => "off",
+            }
+        )
+    }
+}
+
+impl MenuItemActionTrait for MenuItemBuzzer {
+    fn call(&self, btn: u8, _amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => MenuItemAction::MovePositionUp,
+            2 => {
+                menu.buzzer.0 = !menu.buzzer.0;
+                menu.buzzer.1 = true;
+                MenuItemAction::None
+            }
+            3 => MenuItemAction::MovePositionDown,
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemPostPulse {}
+impl MenuItemTextTrait for MenuItemPostPulse {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!(
+            "POST pulse: {}",
+            match menu.post_pulse.0 {
+                true => "on",
+                false => "off",
+            }
+        )
+    }
+}
+
+impl MenuItemActionTrait for MenuItemPostPulse {
+    fn call(&self, btn: u8, _amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => MenuItemAction::MovePositionUp,
+            2 => {
+                menu.post_pulse.0 = !menu.post_pulse.0;
+                menu.post_pulse.1 = true;
+                MenuItemAction::None
+            }
+            3 => MenuItemAction::MovePositionDown,
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemIdleTimeout {}
+impl MenuItemTextTrait for MenuItemIdleTimeout {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        match menu.idle_timeout.0 {
+            0 => format_static!("Idle off: never"),
+            x => format_static!("Idle off: {:03}min", x),
+        }
+    }
+}
+
+impl MenuItemActionTrait for MenuItemIdleTimeout {
+    fn call(&self, btn: u8, amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => {
+                menu.idle_timeout.0 = min(menu.idle_timeout.0 + amount as u16, 480);
+                menu.idle_timeout.1 = true;
+                MenuItemAction::None
+            }
+            2 => MenuItemAction::Back,
+            3 => {
+                menu.idle_timeout.0 = menu.idle_timeout.0.saturating_sub(amount as u16);
+                menu.idle_timeout.1 = true;
+                MenuItemAction::None
+            }
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemManualTempMax {}
+impl MenuItemTextTrait for MenuItemManualTempMax {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("Max temp: {:03}C", menu.manual_temp_max.0)
+    }
+}
+
+impl MenuItemActionTrait for MenuItemManualTempMax {
+    fn call(&self, btn: u8, amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => {
+                menu.manual_temp_max.0 = min(menu.manual_temp_max.0 + amount as u16, 300);
+                menu.manual_temp_max.1 = true;
+                MenuItemAction::None
+            }
+            2 => MenuItemAction::Back,
+            3 => {
+                menu.manual_temp_max.0 =
+                    menu.manual_temp_max.0.saturating_sub(amount as u16).max(50);
+                menu.manual_temp_max.1 = true;
+                MenuItemAction::None
+            }
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemManualTimeMax {}
+impl MenuItemTextTrait for MenuItemManualTimeMax {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("Max time: {:03}min", menu.manual_time_max.0)
+    }
+}
+
+impl MenuItemActionTrait for MenuItemManualTimeMax {
+    fn call(&self, btn: u8, amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => {
+                menu.manual_time_max.0 = min(menu.manual_time_max.0 + amount as u16, 120);
+                menu.manual_time_max.1 = true;
+                MenuItemAction::None
+            }
+            2 => MenuItemAction::Back,
+            3 => {
+                menu.manual_time_max.0 =
+                    menu.manual_time_max.0.saturating_sub(amount as u16).max(1);
+                menu.manual_time_max.1 = true;
+                MenuItemAction::None
+            }
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemHeaterWatts {}
+impl MenuItemTextTrait for MenuItemHeaterWatts {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("Heater: {:04}W", menu.heater_watts.0)
+    }
+}
+
+impl MenuItemActionTrait for MenuItemHeaterWatts {
+    fn call(&self, btn: u8, amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => {
+                menu.heater_watts.0 = min(menu.heater_watts.0 + amount as u16 * 10, 5000);
+                menu.heater_watts.1 = true;
+                MenuItemAction::None
+            }
+            2 => MenuItemAction::Back,
+            3 => {
+                menu.heater_watts.0 = menu.heater_watts.0.saturating_sub(amount as u16 * 10);
+                menu.heater_watts.1 = true;
+                MenuItemAction::None
+            }
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemStatsOnTime {}
+impl MenuItemTextTrait for MenuItemStatsOnTime {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("On time: {:.1}h", menu.stats.heater_on as f32 / 3600.0)
+    }
+}
+
+struct MenuItemStatsEnergy {}
+impl MenuItemTextTrait for MenuItemStatsEnergy {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("Energy: {:.2}kWh", menu.stats.energy as f32 / 3_600_000.0)
+    }
+}
+
+struct MenuItemStatsRuns {}
+impl MenuItemTextTrait for MenuItemStatsRuns {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("Runs: {}", menu.stats.runs)
+    }
+}
+
+struct MenuItemStatsFaults {}
+impl MenuItemTextTrait for MenuItemStatsFaults {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("Faults: {}", menu.stats.faults)
+    }
+}
+
+struct MenuItemStatsReset {}
+impl MenuItemTextTrait for MenuItemStatsReset {
+    fn get(&self, _menu: &Menu) -> StaticString<20> {
+        format_static!("Reset stats")
+    }
+}
+
+impl MenuItemActionTrait for MenuItemStatsReset {
+    fn call(&self, btn: u8, _amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => MenuItemAction::MovePositionUp,
+            2 => {
+                menu.stats = StatsData::default();
+                menu.stats_reset = true;
+                MenuItemAction::None
+            }
+            3 => MenuItemAction::MovePositionDown,
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemZoneSelect {}
+impl MenuItemTextTrait for MenuItemZoneSelect {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        let zone = &menu.zones.0[menu.zone_edit];
+        format_static!(
+            "Zone {}: {:+03}C {}",
+            menu.zone_edit + 2,
+            zone.offset,
+            match zone.enabled {
+                true => "on",
+                false => "off",
+            }
+        )
+    }
+}
+
+impl MenuItemActionTrait for MenuItemZoneSelect {
+    fn call(&self, btn: u8, _amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => MenuItemAction::MovePositionUp,
+            2 => {
+                menu.zone_edit = (menu.zone_edit + 1) % AUX_ZONES;
+                MenuItemAction::None
+            }
+            3 => MenuItemAction::MovePositionDown,
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemZoneEnabled {}
+impl MenuItemTextTrait for MenuItemZoneEnabled {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!(
+            "Zone enabled: {}",
+            match menu.zones.0[menu.zone_edit].enabled {
+                true => "true",
+                false => "false",
+            }
+        )
+    }
+}
+
+impl MenuItemActionTrait for MenuItemZoneEnabled {
+    fn call(&self, btn: u8, _amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => MenuItemAction::MovePositionUp,
+            2 => {
+                let zone = &mut menu.zones.0[menu.zone_edit];
+                zone.enabled = !zone.enabled;
+                menu.zones.1 = true;
+                MenuItemAction::None
+            }
+            3 => MenuItemAction::MovePositionDown,
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemZoneOffset {}
+impl MenuItemTextTrait for MenuItemZoneOffset {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("Zone offset: {:03}", menu.zones.0[menu.zone_edit].offset)
+    }
+}
+
+impl MenuItemActionTrait for MenuItemZoneOffset {
+    fn call(&self, btn: u8, amount: u8, menu: &mut Menu) -> MenuItemAction {
+        let zone = &mut menu.zones.0[menu.zone_edit];
+        match btn {
+            1 => {
+                zone.offset += amount as i16;
+                menu.zones.1 = true;
+                MenuItemAction::None
+            }
+            2 => MenuItemAction::Back,
+            3 => {
+                zone.offset -= amount as i16;
+                menu.zones.1 = true;
+                MenuItemAction::None
+            }
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemZoneP {}
+impl MenuItemTextTrait for MenuItemZoneP {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("Zone P: {:03.02}", menu.zones.0[menu.zone_edit].pid_p)
+    }
+}
+
+impl MenuItemActionTrait for MenuItemZoneP {
+    fn call(&self, btn: u8, amount: u8, menu: &mut Menu) -> MenuItemAction {
+        let zone = &mut menu.zones.0[menu.zone_edit];
+        match btn {
+            1 => {
+                zone.pid_p += 0.01 * (amount as f32);
+                menu.zones.1 = true;
+                MenuItemAction::None
+            }
+            2 => MenuItemAction::Back,
+            3 => {
+                zone.pid_p -= 0.01 * (amount as f32);
+                menu.zones.1 = true;
+                MenuItemAction::None
+            }
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemZoneI {}
+impl MenuItemTextTrait for MenuItemZoneI {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("Zone I: {:.3}", menu.zones.0[menu.zone_edit].pid_i)
+    }
+}
+
+impl MenuItemActionTrait for MenuItemZoneI {
+    fn call(&self, btn: u8, amount: u8, menu: &mut Menu) -> MenuItemAction {
+        let zone = &mut menu.zones.0[menu.zone_edit];
+        match btn {
+            1 => {
+                zone.pid_i += 0.001 * (amount as f32);
+                menu.zones.1 = true;
+                MenuItemAction::None
+            }
+            2 => MenuItemAction::Back,
+            3 => {
+                zone.pid_i -= 0.001 * (amount as f32);
+                menu.zones.1 = true;
+                MenuItemAction::None
+            }
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemZoneD {}
+impl MenuItemTextTrait for MenuItemZoneD {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("Zone D: {:03.02}", menu.zones.0[menu.zone_edit].pid_d)
+    }
+}
+
+impl MenuItemActionTrait for MenuItemZoneD {
+    fn call(&self, btn: u8, amount: u8, menu: &mut Menu) -> MenuItemAction {
+        let zone = &mut menu.zones.0[menu.zone_edit];
+        match btn {
+            1 => {
+                zone.pid_d += 0.01 * (amount as f32);
+                menu.zones.1 = true;
+                MenuItemAction::None
+            }
+            2 => MenuItemAction::Back,
+            3 => {
+                zone.pid_d -= 0.01 * (amount as f32);
+                menu.zones.1 = true;
+                MenuItemAction::None
+            }
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemPowerBudget {}
+impl MenuItemTextTrait for MenuItemPowerBudget {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("Power budget: {:03}%", menu.power_budget.0)
+    }
+}
+
+impl MenuItemActionTrait for MenuItemPowerBudget {
+    fn call(&self, btn: u8, amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => {
+                menu.power_budget.0 =
+                    min(menu.power_budget.0 + amount as u16 * 5, ZONES as u16 * 100);
+                menu.power_budget.1 = true;
+                MenuItemAction::None
+            }
+            2 => MenuItemAction::Back,
+            3 => {
+                menu.power_budget.0 = menu
+                    .power_budget
+                    .0
+                    .saturating_sub(amount as u16 * 5)
+                    .max(10);
+                menu.power_budget.1 = true;
+                MenuItemAction::None
+            }
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemPowerPolicy {}
+impl MenuItemTextTrait for MenuItemPowerPolicy {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!(
+            "Sharing: {}",
+            match menu.power_policy.0 {
+                AllocPolicyEnum::Priority => "priority",
+                AllocPolicyEnum::FairShare => "fair",
+            }
+        )
+    }
+}
+
+impl MenuItemActionTrait for MenuItemPowerPolicy {
+    fn call(&self, btn: u8, _amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => MenuItemAction::MovePositionUp,
+            2 => {
+                menu.power_policy.0 = match menu.power_policy.0 {
+                    AllocPolicyEnum::Priority => AllocPolicyEnum::FairShare,
+                    AllocPolicyEnum::FairShare => AllocPolicyEnum::Priority,
+                };
+                menu.power_policy.1 = true;
+                MenuItemAction::None
+            }
+            3 => MenuItemAction::MovePositionDown,
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemInterlockSelect {}
+impl MenuItemTextTrait for MenuItemInterlockSelect {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!(
+            "Interlock {}: {}",
+            menu.interlock_edit + 1,
+            match menu.interlocks.0[menu.interlock_edit].enabled {
+                true => "on",
+                false => "off",
+            }
+        )
+    }
+}
+
+impl MenuItemActionTrait for MenuItemInterlockSelect {
+    fn call(&self, btn: u8, _amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => MenuItemAction::MovePositionUp,
+            2 => {
+                menu.interlock_edit = (menu.interlock_edit + 1) % INTERLOCKS;
+                MenuItemAction::None
+            }
+            3 => MenuItemAction::MovePositionDown,
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemInterlockEnabled {}
+impl MenuItemTextTrait for MenuItemInterlockEnabled {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!(
+            "Lock enabled: {}",
+            match menu.interlocks.0[menu.interlock_edit].enabled {
+                true => "true",
+                false => "false",
+            }
+        )
+    }
+}
+
+impl MenuItemActionTrait for MenuItemInterlockEnabled {
+    fn call(&self, btn: u8, _amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => MenuItemAction::MovePositionUp,
+            2 => {
+                let interlock = &mut menu.interlocks.0[menu.interlock_edit];
+                interlock.enabled = !interlock.enabled;
+                menu.interlocks.1 = true;
+                MenuItemAction::None
+            }
+            3 => MenuItemAction::MovePositionDown,
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemInterlockAction {}
+impl MenuItemTextTrait for MenuItemInterlockAction {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!(
+            "On open: {}",
+            match menu.interlocks.0[menu.interlock_edit].action {
+                InterlockActionEnum::Pause => "pause",
+                InterlockActionEnum::Abort => "abort",
+            }
+        )
+    }
+}
+
+impl MenuItemActionTrait for MenuItemInterlockAction {
+    fn call(&self, btn: u8, _amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => MenuItemAction::MovePositionUp,
+            2 => {
+                let interlock = &mut menu.interlocks.0[menu.interlock_edit];
+                interlock.action = match interlock.action {
+                    InterlockActionEnum::Pause => InterlockActionEnum::Abort,
+                    InterlockActionEnum::Abort => InterlockActionEnum::Pause,
+                };
+                menu.interlocks.1 = true;
+                MenuItemAction::None
+            }
+            3 => MenuItemAction::MovePositionDown,
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+struct MenuItemStatsInterlockTrips {}
+impl MenuItemTextTrait for MenuItemStatsInterlockTrips {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        format_static!("Lock trips: {}", menu.stats.interlock_trips)
+    }
+}
+
+struct MenuItemStatsTrip {
+    index: usize,
+}
+impl MenuItemTextTrait for MenuItemStatsTrip {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        match menu.stats.trips.get(self.index).copied().flatten() {
+            Some(trip) => format_static!(
+                "{}: in{} {} {:.1}h",
+                self.index + 1,
+                trip.input,
+                match trip.action {
+                    InterlockActionEnum::Pause => "pause",
+                    InterlockActionEnum::Abort => "abort",
+                },
+                trip.heater_on as f32 / 3600.0
+            ),
+            None => format_static!("{}: -", self.index + 1),
+        }
+    }
+}
+
+struct MenuItemTop {}
+impl MenuItemTextTrait for MenuItemTop {
+    fn get(&self, menu: &Menu) -> StaticString<20> {
+        match menu.post_result {
+            None => format_static!("Wait for POST"),
+            Some(_) => format_static!("Menu"),
+        }
+    }
+}
+
+impl MenuItemActionTrait for MenuItemTop {
+    fn call(&self, btn: u8, _amount: u8, menu: &mut Menu) -> MenuItemAction {
+        match btn {
+            1 => MenuItemAction::MovePositionUp,
+            //heater doesn't take commands during POST, runs start after its result
+            2 => match menu.post_result {
+                None => MenuItemAction::None,
+                Some(_) => MenuItemAction::OpenMenu(&MENU_MAIN),
+            },
+            3 => MenuItemAction::MovePositionDown,
+            _ => MenuItemAction::None,
+        }
+    }
+}
+
+//menus
+const MENU_TOP: &MenuType = &[
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemTop {}),
+        action: MenuItemAction::Custom(&MenuItemTop {}),
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemDiagPost {}),
+        action: MenuItemAction::None,
+    },
+];
+
+const MENU_MAIN: &MenuType = &[
+    MenuItem {
+        text: MenuItemText::Static("Target temp"),
+        action: MenuItemAction::OpenMenu(&MENU_TARGET_TEMP),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Pid"),
+        action: MenuItemAction::OpenMenu(&MENU_PID),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Settings"),
+        action: MenuItemAction::OpenMenu(&MENU_SETTINGS),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Diagnostics"),
+        action: MenuItemAction::OpenMenu(&MENU_DIAGNOSTICS),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Statistics"),
+        action: MenuItemAction::OpenMenu(&MENU_STATISTICS),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Back"),
+        action: MenuItemAction::Back,
+    },
+];
+
+const MENU_TARGET_TEMP: &MenuType = &[
+    MenuItem {
+        text: MenuItemText::Static("Static target temp"),
+        action: MenuItemAction::OpenMenu(&MENU_TARGET_TEMP_STATIC),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Bake (no idle off)"),
+        action: MenuItemAction::OpenMenu(&MENU_TARGET_TEMP_BAKE),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Temp profile A"),
+        action: MenuItemAction::OpenMenu(&MENU_TARGET_TEMP_PROFILE_A),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Manual power"),
+        action: MenuItemAction::OpenMenu(&MENU_TARGET_MANUAL),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Back"),
+        action: MenuItemAction::Back,
+    },
+];
+
+const MENU_TARGET_TEMP_STATIC: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemTargetTempStatic {}),
+    action: MenuItemAction::Custom(&MenuItemTargetTempStatic {}),
+}];
+
+const MENU_TARGET_TEMP_PROFILE_A: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemTargetTempProfileA {}),
+    action: MenuItemAction::Custom(&MenuItemTargetTempProfileA {}),
+}];
+
+const MENU_TARGET_TEMP_BAKE: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemTargetTempBake {}),
+    action: MenuItemAction::Custom(&MenuItemTargetTempBake {}),
+}];
+
+const MENU_TARGET_MANUAL: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemTargetManual {}),
+    action: MenuItemAction::Custom(&MenuItemTargetManual {}),
+}];
+
+const MENU_PID: &MenuType = &[
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemPidUsePid {}),
+        action: MenuItemAction::Custom(&MenuItemPidUsePid {}),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Set manual"),
+        action: MenuItemAction::OpenMenu(&MENU_PID_MANUAL),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Gain schedule"),
+        action: MenuItemAction::OpenMenu(&MENU_PID_SCHEDULE),
+    },
+    MenuItem {
+        text: MenuItemText::Static("AutoTune"),
+        action: MenuItemAction::OpenMenu(&MENU_PID_AUTOTUNE),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Characterize"),
+        action: MenuItemAction::OpenMenu(&MENU_PID_CHARACTERIZE),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Feed-forward"),
+        action: MenuItemAction::OpenMenu(&MENU_PID_FEEDFORWARD),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Cascade"),
+        action: MenuItemAction::OpenMenu(&MENU_PID_CASCADE),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Back"),
+        action: MenuItemAction::Back,
+    },
+];
+
+const MENU_PID_P: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemPidP {}),
+    action: MenuItemAction::Custom(&MenuItemPidP {}),
+}];
+
+const MENU_PID_I: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemPidI {}),
+    action: MenuItemAction::Custom(&MenuItemPidI {}),
+}];
+
+const MENU_PID_D: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemPidD {}),
+    action: MenuItemAction::Custom(&MenuItemPidD {}),
+}];
+
+const MENU_PID_I_MIN: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemPidIMin {}),
+    action: MenuItemAction::Custom(&MenuItemPidIMin {}),
+}];
+
+const MENU_PID_I_MAX: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemPidIMax {}),
+    action: MenuItemAction::Custom(&MenuItemPidIMax {}),
+}];
+
+const MENU_PID_MANUAL: &MenuType = &[
+    MenuItem {
+        text: MenuItemText::Static("Set P"),
+        action: MenuItemAction::OpenMenu(&MENU_PID_P),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Set I"),
+        action: MenuItemAction::OpenMenu(&MENU_PID_I),
+    },
+    MenuItem {
         text: MenuItemText::Static("Set D"),
         action: MenuItemAction::OpenMenu(&MENU_PID_D),
     },
+    MenuItem {
+        text: MenuItemText::Static("Set I min"),
+        action: MenuItemAction::OpenMenu(&MENU_PID_I_MIN),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Set I max"),
+        action: MenuItemAction::OpenMenu(&MENU_PID_I_MAX),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Back"),
+        action: MenuItemAction::Back,
+    },
+];
+
+const MENU_PID_SCHEDULE: &MenuType = &[
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemPidScheduleUse {}),
+        action: MenuItemAction::Custom(&MenuItemPidScheduleUse {}),
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemPidBandSelect {}),
+        action: MenuItemAction::Custom(&MenuItemPidBandSelect {}),
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemPidBandEnabled {}),
+        action: MenuItemAction::Custom(&MenuItemPidBandEnabled {}),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Set band temp"),
+        action: MenuItemAction::OpenMenu(&MENU_PID_BAND_TEMP),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Set band P"),
+        action: MenuItemAction::OpenMenu(&MENU_PID_BAND_P),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Set band I"),
+        action: MenuItemAction::OpenMenu(&MENU_PID_BAND_I),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Set band D"),
+        action: MenuItemAction::OpenMenu(&MENU_PID_BAND_D),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Back"),
+        action: MenuItemAction::Back,
+    },
+];
+
+const MENU_PID_BAND_TEMP: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemPidBandTemp {}),
+    action: MenuItemAction::Custom(&MenuItemPidBandTemp {}),
+}];
+
+const MENU_PID_BAND_P: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemPidBandP {}),
+    action: MenuItemAction::Custom(&MenuItemPidBandP {}),
+}];
+
+const MENU_PID_BAND_I: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemPidBandI {}),
+    action: MenuItemAction::Custom(&MenuItemPidBandI {}),
+}];
+
+const MENU_PID_BAND_D: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemPidBandD {}),
+    action: MenuItemAction::Custom(&MenuItemPidBandD {}),
+}];
+
+const MENU_PID_AUTOTUNE: &MenuType = &[
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemPidAutoTune {}),
+        action: MenuItemAction::Custom(&MenuItemPidAutoTune {}),
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemAutoTuneSweep {}),
+        action: MenuItemAction::Custom(&MenuItemAutoTuneSweep {}),
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemAutoTuneP {}),
+        action: MenuItemAction::None,
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemAutoTuneI {}),
+        action: MenuItemAction::None,
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemAutoTuneD {}),
+        action: MenuItemAction::None,
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemAutoTuneRule {}),
+        action: MenuItemAction::Custom(&MenuItemAutoTuneRule {}),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Tune settings"),
+        action: MenuItemAction::OpenMenu(&MENU_PID_AUTOTUNE_SETTINGS),
+    },
+];
+
+const MENU_PID_AUTOTUNE_REVIEW: &MenuType = &[
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemAutoTuneBand {}),
+        action: MenuItemAction::Custom(&MenuItemAutoTuneBand {}),
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemAutoTuneP {}),
+        action: MenuItemAction::None,
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemAutoTuneI {}),
+        action: MenuItemAction::None,
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemAutoTuneD {}),
+        action: MenuItemAction::None,
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemAutoTuneOscillation {}),
+        action: MenuItemAction::None,
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemAutoTuneQuality {}),
+        action: MenuItemAction::None,
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemAutoTuneRule {}),
+        action: MenuItemAction::Custom(&MenuItemAutoTuneRule {}),
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemAutoTuneCompare {}),
+        action: MenuItemAction::Custom(&MenuItemAutoTuneCompare {}),
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemAutoTuneAccept {}),
+        action: MenuItemAction::Custom(&MenuItemAutoTuneAccept {}),
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemAutoTuneReject {}),
+        action: MenuItemAction::Custom(&MenuItemAutoTuneReject {}),
+    },
+];
+
+const MENU_PID_AUTOTUNE_SETTINGS: &MenuType = &[
+    MenuItem {
+        text: MenuItemText::Static("Relay amplitude"),
+        action: MenuItemAction::OpenMenu(&MENU_PID_AUTOTUNE_AMPLITUDE),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Hysteresis"),
+        action: MenuItemAction::OpenMenu(&MENU_PID_AUTOTUNE_HYSTERESIS),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Cycles"),
+        action: MenuItemAction::OpenMenu(&MENU_PID_AUTOTUNE_CYCLES),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Back"),
+        action: MenuItemAction::Back,
+    },
+];
+
+const MENU_PID_AUTOTUNE_AMPLITUDE: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemAutoTuneAmplitude {}),
+    action: MenuItemAction::Custom(&MenuItemAutoTuneAmplitude {}),
+}];
+
+const MENU_PID_AUTOTUNE_HYSTERESIS: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemAutoTuneHysteresis {}),
+    action: MenuItemAction::Custom(&MenuItemAutoTuneHysteresis {}),
+}];
+
+const MENU_PID_AUTOTUNE_CYCLES: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemAutoTuneCycles {}),
+    action: MenuItemAction::Custom(&MenuItemAutoTuneCycles {}),
+}];
+
+const MENU_PID_CHARACTERIZE: &MenuType = &[
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemCharacterize {}),
+        action: MenuItemAction::Custom(&MenuItemCharacterize {}),
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemPlantModel {}),
+        action: MenuItemAction::None,
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemImcP {}),
+        action: MenuItemAction::None,
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemImcI {}),
+        action: MenuItemAction::None,
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemImcD {}),
+        action: MenuItemAction::None,
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemImcApply {}),
+        action: MenuItemAction::Custom(&MenuItemImcApply {}),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Set step duty"),
+        action: MenuItemAction::OpenMenu(&MENU_PID_CHARACTERIZE_DUTY),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Set lambda"),
+        action: MenuItemAction::OpenMenu(&MENU_PID_CHARACTERIZE_LAMBDA),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Back"),
+        action: MenuItemAction::Back,
+    },
+];
+
+const MENU_PID_CHARACTERIZE_DUTY: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemCharacterizeDuty {}),
+    action: MenuItemAction::Custom(&MenuItemCharacterizeDuty {}),
+}];
+
+const MENU_PID_CHARACTERIZE_LAMBDA: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemImcLambda {}),
+    action: MenuItemAction::Custom(&MenuItemImcLambda {}),
+}];
+
+const MENU_PID_FEEDFORWARD: &MenuType = &[
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemFeedForwardUse {}),
+        action: MenuItemAction::Custom(&MenuItemFeedForwardUse {}),
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemFeedForwardLearn {}),
+        action: MenuItemAction::Custom(&MenuItemFeedForwardLearn {}),
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemDutyMap { first: 0 }),
+        action: MenuItemAction::None,
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemDutyMap { first: 2 }),
+        action: MenuItemAction::None,
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemDutyMap { first: 4 }),
+        action: MenuItemAction::None,
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemDutyMapSeed {}),
+        action: MenuItemAction::Custom(&MenuItemDutyMapSeed {}),
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemDutyMapClear {}),
+        action: MenuItemAction::Custom(&MenuItemDutyMapClear {}),
+    },
     MenuItem {
         text: MenuItemText::Static("Back"),
         action: MenuItemAction::Back,
     },
 ];
 
-const MENU_PID_AUTOTUNE: &MenuType = &[
+const MENU_PID_CASCADE: &MenuType = &[
     MenuItem {
-        text: MenuItemText::Render(&MenuItemPidAutoTune {}),
-        action: MenuItemAction::Custom(&MenuItemPidAutoTune {}),
+        text: MenuItemText::Render(&MenuItemCascadeUse {}),
+        action: MenuItemAction::Custom(&MenuItemCascadeUse {}),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Set outer P"),
+        action: MenuItemAction::OpenMenu(&MENU_PID_CASCADE_P),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Set outer I"),
+        action: MenuItemAction::OpenMenu(&MENU_PID_CASCADE_I),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Set max over"),
+        action: MenuItemAction::OpenMenu(&MENU_PID_CASCADE_MAX_OVER),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Back"),
+        action: MenuItemAction::Back,
+    },
+];
+
+const MENU_PID_CASCADE_P: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemCascadeP {}),
+    action: MenuItemAction::Custom(&MenuItemCascadeP {}),
+}];
+
+const MENU_PID_CASCADE_I: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemCascadeI {}),
+    action: MenuItemAction::Custom(&MenuItemCascadeI {}),
+}];
+
+const MENU_PID_CASCADE_MAX_OVER: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemCascadeMaxOver {}),
+    action: MenuItemAction::Custom(&MenuItemCascadeMaxOver {}),
+}];
+
+const MENU_SETTINGS: &MenuType = &[
+    MenuItem {
+        text: MenuItemText::Static("Temp wait time"),
+        action: MenuItemAction::OpenMenu(&MENU_SETTINGS_WAIT_TIME),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Profile extra time"),
+        action: MenuItemAction::OpenMenu(&MENU_SETTINGS_EXTRA_TIME),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Temp offset"),
+        action: MenuItemAction::OpenMenu(&MENU_SETTINGS_TEMP_OFFSET),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Temp lead offset"),
+        action: MenuItemAction::OpenMenu(&MENU_SETTINGS_TEMP_LEAD_OFFSET),
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemLagCompensation {}),
+        action: MenuItemAction::Custom(&MenuItemLagCompensation {}),
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemPlateSensor {}),
+        action: MenuItemAction::Custom(&MenuItemPlateSensor {}),
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemBoardProbe {}),
+        action: MenuItemAction::Custom(&MenuItemBoardProbe {}),
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemSyncSource {}),
+        action: MenuItemAction::Custom(&MenuItemSyncSource {}),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Output"),
+        action: MenuItemAction::OpenMenu(&MENU_SETTINGS_OUTPUT),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Bang-bang"),
+        action: MenuItemAction::OpenMenu(&MENU_SETTINGS_BANG_BANG),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Fan"),
+        action: MenuItemAction::OpenMenu(&MENU_SETTINGS_FAN),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Zones"),
+        action: MenuItemAction::OpenMenu(&MENU_SETTINGS_ZONES),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Interlocks"),
+        action: MenuItemAction::OpenMenu(&MENU_SETTINGS_INTERLOCKS),
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemBuzzer {}),
+        action: MenuItemAction::Custom(&MenuItemBuzzer {}),
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemPostPulse {}),
+        action: MenuItemAction::Custom(&MenuItemPostPulse {}),
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemIdleTimeout {}),
+        action: MenuItemAction::OpenMenu(&MENU_SETTINGS_IDLE_TIMEOUT),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Manual limits"),
+        action: MenuItemAction::OpenMenu(&MENU_SETTINGS_MANUAL),
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemHeaterWatts {}),
+        action: MenuItemAction::OpenMenu(&MENU_SETTINGS_HEATER_WATTS),
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemControlPeriod {}),
+        action: MenuItemAction::OpenMenu(&MENU_SETTINGS_CONTROL_PERIOD),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Back"),
+        action: MenuItemAction::Back,
+    },
+];
+
+const MENU_SETTINGS_WAIT_TIME: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemWaitTime {}),
+    action: MenuItemAction::Custom(&MenuItemWaitTime {}),
+}];
+
+const MENU_SETTINGS_EXTRA_TIME: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemExtraTime {}),
+    action: MenuItemAction::Custom(&MenuItemExtraTime {}),
+}];
+
+const MENU_SETTINGS_TEMP_OFFSET: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemTempOffset {}),
+    action: MenuItemAction::Custom(&MenuItemTempOffset {}),
+}];
+
+const MENU_SETTINGS_TEMP_LEAD_OFFSET: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemTempLeadOffset {}),
+    action: MenuItemAction::Custom(&MenuItemTempLeadOffset {}),
+}];
+
+const MENU_SETTINGS_OUTPUT: &MenuType = &[
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemOutputMode {}),
+        action: MenuItemAction::Custom(&MenuItemOutputMode {}),
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemOutputWindow {}),
+        action: MenuItemAction::OpenMenu(&MENU_SETTINGS_OUTPUT_WINDOW),
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemOutputMinOn {}),
+        action: MenuItemAction::OpenMenu(&MENU_SETTINGS_OUTPUT_MIN_ON),
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemOutputMinOff {}),
+        action: MenuItemAction::OpenMenu(&MENU_SETTINGS_OUTPUT_MIN_OFF),
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemMaxDuty {}),
+        action: MenuItemAction::OpenMenu(&MENU_SETTINGS_MAX_DUTY),
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemMaxDutySoak {}),
+        action: MenuItemAction::OpenMenu(&MENU_SETTINGS_MAX_DUTY_SOAK),
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemSoftStart {}),
+        action: MenuItemAction::OpenMenu(&MENU_SETTINGS_SOFT_START),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Back"),
+        action: MenuItemAction::Back,
+    },
+];
+
+const MENU_SETTINGS_OUTPUT_WINDOW: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemOutputWindow {}),
+    action: MenuItemAction::Custom(&MenuItemOutputWindow {}),
+}];
+
+const MENU_SETTINGS_OUTPUT_MIN_ON: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemOutputMinOn {}),
+    action: MenuItemAction::Custom(&MenuItemOutputMinOn {}),
+}];
+
+const MENU_SETTINGS_OUTPUT_MIN_OFF: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemOutputMinOff {}),
+    action: MenuItemAction::Custom(&MenuItemOutputMinOff {}),
+}];
+
+const MENU_SETTINGS_MAX_DUTY: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemMaxDuty {}),
+    action: MenuItemAction::Custom(&MenuItemMaxDuty {}),
+}];
+
+const MENU_SETTINGS_MAX_DUTY_SOAK: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemMaxDutySoak {}),
+    action: MenuItemAction::Custom(&MenuItemMaxDutySoak {}),
+}];
+
+const MENU_SETTINGS_SOFT_START: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemSoftStart {}),
+    action: MenuItemAction::Custom(&MenuItemSoftStart {}),
+}];
+
+const MENU_SETTINGS_BANG_BANG: &MenuType = &[
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemBangBelow {}),
+        action: MenuItemAction::OpenMenu(&MENU_SETTINGS_BANG_BELOW),
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemBangAbove {}),
+        action: MenuItemAction::OpenMenu(&MENU_SETTINGS_BANG_ABOVE),
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemBangMinSwitch {}),
+        action: MenuItemAction::OpenMenu(&MENU_SETTINGS_BANG_MIN_SWITCH),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Back"),
+        action: MenuItemAction::Back,
+    },
+];
+
+const MENU_SETTINGS_BANG_BELOW: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemBangBelow {}),
+    action: MenuItemAction::Custom(&MenuItemBangBelow {}),
+}];
+
+const MENU_SETTINGS_BANG_ABOVE: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemBangAbove {}),
+    action: MenuItemAction::Custom(&MenuItemBangAbove {}),
+}];
+
+const MENU_SETTINGS_BANG_MIN_SWITCH: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemBangMinSwitch {}),
+    action: MenuItemAction::Custom(&MenuItemBangMinSwitch {}),
+}];
+
+const MENU_SETTINGS_CONTROL_PERIOD: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemControlPeriod {}),
+    action: MenuItemAction::Custom(&MenuItemControlPeriod {}),
+}];
+
+const MENU_SETTINGS_FAN: &MenuType = &[
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemFanMode {}),
+        action: MenuItemAction::Custom(&MenuItemFanMode {}),
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemFanCoolingRate {}),
+        action: MenuItemAction::OpenMenu(&MENU_SETTINGS_FAN_COOLING_RATE),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Back"),
+        action: MenuItemAction::Back,
+    },
+];
+
+const MENU_SETTINGS_FAN_COOLING_RATE: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemFanCoolingRate {}),
+    action: MenuItemAction::Custom(&MenuItemFanCoolingRate {}),
+}];
+
+const MENU_SETTINGS_IDLE_TIMEOUT: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemIdleTimeout {}),
+    action: MenuItemAction::Custom(&MenuItemIdleTimeout {}),
+}];
+
+const MENU_SETTINGS_MANUAL: &MenuType = &[
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemManualTempMax {}),
+        action: MenuItemAction::OpenMenu(&MENU_SETTINGS_MANUAL_TEMP_MAX),
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemManualTimeMax {}),
+        action: MenuItemAction::OpenMenu(&MENU_SETTINGS_MANUAL_TIME_MAX),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Back"),
+        action: MenuItemAction::Back,
+    },
+];
+
+const MENU_SETTINGS_MANUAL_TEMP_MAX: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemManualTempMax {}),
+    action: MenuItemAction::Custom(&MenuItemManualTempMax {}),
+}];
+
+const MENU_SETTINGS_MANUAL_TIME_MAX: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemManualTimeMax {}),
+    action: MenuItemAction::Custom(&MenuItemManualTimeMax {}),
+}];
+
+const MENU_SETTINGS_HEATER_WATTS: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemHeaterWatts {}),
+    action: MenuItemAction::Custom(&MenuItemHeaterWatts {}),
+}];
+
+const MENU_SETTINGS_ZONES: &MenuType = &[
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemZoneSelect {}),
+        action: MenuItemAction::Custom(&MenuItemZoneSelect {}),
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemZoneEnabled {}),
+        action: MenuItemAction::Custom(&MenuItemZoneEnabled {}),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Set zone offset"),
+        action: MenuItemAction::OpenMenu(&MENU_SETTINGS_ZONE_OFFSET),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Set zone P"),
+        action: MenuItemAction::OpenMenu(&MENU_SETTINGS_ZONE_P),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Set zone I"),
+        action: MenuItemAction::OpenMenu(&MENU_SETTINGS_ZONE_I),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Set zone D"),
+        action: MenuItemAction::OpenMenu(&MENU_SETTINGS_ZONE_D),
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemPowerBudget {}),
+        action: MenuItemAction::OpenMenu(&MENU_SETTINGS_POWER_BUDGET),
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemPowerPolicy {}),
+        action: MenuItemAction::Custom(&MenuItemPowerPolicy {}),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Back"),
+        action: MenuItemAction::Back,
+    },
+];
+
+const MENU_SETTINGS_ZONE_OFFSET: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemZoneOffset {}),
+    action: MenuItemAction::Custom(&MenuItemZoneOffset {}),
+}];
+
+const MENU_SETTINGS_ZONE_P: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemZoneP {}),
+    action: MenuItemAction::Custom(&MenuItemZoneP {}),
+}];
+
+const MENU_SETTINGS_ZONE_I: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemZoneI {}),
+    action: MenuItemAction::Custom(&MenuItemZoneI {}),
+}];
+
+const MENU_SETTINGS_ZONE_D: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemZoneD {}),
+    action: MenuItemAction::Custom(&MenuItemZoneD {}),
+}];
+
+const MENU_SETTINGS_POWER_BUDGET: &MenuType = &[MenuItem {
+    text: MenuItemText::Render(&MenuItemPowerBudget {}),
+    action: MenuItemAction::Custom(&MenuItemPowerBudget {}),
+}];
+
+const MENU_SETTINGS_INTERLOCKS: &MenuType = &[
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemInterlockSelect {}),
+        action: MenuItemAction::Custom(&MenuItemInterlockSelect {}),
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemInterlockEnabled {}),
+        action: MenuItemAction::Custom(&MenuItemInterlockEnabled {}),
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemInterlockAction {}),
+        action: MenuItemAction::Custom(&MenuItemInterlockAction {}),
+    },
+    MenuItem {
+        text: MenuItemText::Static("Back"),
+        action: MenuItemAction::Back,
+    },
+];
+
+const MENU_DIAGNOSTICS: &MenuType = &[
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemDiagDieTemp {}),
+        action: MenuItemAction::None,
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemDiagVsys {}),
+        action: MenuItemAction::None,
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemDiagJitter {}),
+        action: MenuItemAction::None,
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemDiagOverruns {}),
+        action: MenuItemAction::None,
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemDiagPost {}),
+        action: MenuItemAction::None,
+    },
+    MenuItem {
+        text: MenuItemText::Static("Back"),
+        action: MenuItemAction::Back,
+    },
+];
+
+const MENU_STATISTICS: &MenuType = &[
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemStatsOnTime {}),
+        action: MenuItemAction::None,
+    },
+    MenuItem {
+        text: MenuItemText::Render(&MenuItemStatsEnergy {}),
+        action: MenuItemAction::None,
     },
     MenuItem {
-        text: MenuItemText::Render(&MenuItemPidP {}),
+        text: MenuItemText::Render(&MenuItemStatsRuns {}),
         action: MenuItemAction::None,
     },
     MenuItem {
-        text: MenuItemText::Render(&MenuItemPidI {}),
+        text: MenuItemText::Render(&MenuItemStatsFaults {}),
         action: MenuItemAction::None,
     },
     MenuItem {
-        text: MenuItemText::Render(&MenuItemPidD {}),
+        text: MenuItemText::Render(&MenuItemStatsInterlockTrips {}),
         action: MenuItemAction::None,
     },
-];
-
-const MENU_SETTINGS: &MenuType = &[
     MenuItem {
-        text: MenuItemText::Static("Temp wait time"),
-        action: MenuItemAction::OpenMenu(&MENU_SETTINGS_WAIT_TIME),
+        text: MenuItemText::Render(&MenuItemStatsTrip { index: 0 }),
+        action: MenuItemAction::None,
     },
     MenuItem {
-        text: MenuItemText::Static("Profile extra time"),
-        action: MenuItemAction::OpenMenu(&MENU_SETTINGS_EXTRA_TIME),
+        text: MenuItemText::Render(&MenuItemStatsTrip { index: 1 }),
+        action: MenuItemAction::None,
     },
     MenuItem {
-        text: MenuItemText::Static("Temp offset"),
-        action: MenuItemAction::OpenMenu(&MENU_SETTINGS_TEMP_OFFSET),
+        text: MenuItemText::Render(&MenuItemStatsTrip { index: 2 }),
+        action: MenuItemAction::None,
     },
     MenuItem {
-        text: MenuItemText::Static("Temp lead offset"),
-        action: MenuItemAction::OpenMenu(&MENU_SETTINGS_TEMP_LEAD_OFFSET),
+        text: MenuItemText::Render(&MenuItemStatsReset {}),
+        action: MenuItemAction::Custom(&MenuItemStatsReset {}),
     },
     MenuItem {
         text: MenuItemText::Static("Back"),
@@ -510,35 +3408,29 @@ const MENU_SETTINGS: &MenuType = &[
     },
 ];
 
-const MENU_SETTINGS_WAIT_TIME: &MenuType = &[MenuItem {
-    text: MenuItemText::Render(&MenuItemWaitTime {}),
-    action: MenuItemAction::Custom(&MenuItemWaitTime {}),
-}];
-
-const MENU_SETTINGS_EXTRA_TIME: &MenuType = &[MenuItem {
-    text: MenuItemText::Render(&MenuItemExtraTime {}),
-    action: MenuItemAction::Custom(&MenuItemExtraTime {}),
-}];
-
-const MENU_SETTINGS_TEMP_OFFSET: &MenuType = &[MenuItem {
-    text: MenuItemText::Render(&MenuItemTempOffset {}),
-    action: MenuItemAction::Custom(&MenuItemTempOffset {}),
-}];
-
-const MENU_SETTINGS_TEMP_LEAD_OFFSET: &MenuType = &[MenuItem {
-    text: MenuItemText::Render(&MenuItemTempLeadOffset {}),
-    action: MenuItemAction::Custom(&MenuItemTempLeadOffset {}),
-}];
-
 #[derive(Debug)]
 pub(crate) enum SyncMenuStateEnum {
     PidAutoTune {
         iteration: u8,
-        pid_p: f32,
-        pid_i: f32,
-        pid_d: f32,
+        result: RelayResult,
         done: bool,
     },
+    Characterize {
+        elapsed: u16,
+        model: Option<PlantModel>,
+        done: bool,
+    },
+    DutyMap(DutyMap),
+    Diagnostics {
+        die_temp: f32,
+        vsys: f32,
+    },
+    LoopJitter(JitterReport),
+    Post(PostResultEnum),
+    IdleOff,
+    InterlockAbort,
+    IdentifyRefused,
+    Stats(StatsData),
 }
 
 pub(crate) enum PidAutoTuneInProgressEnum {
@@ -558,18 +3450,83 @@ pub(crate) struct Menu<'a> {
     display_tx: SyncStateChannelSender<'a, SyncDisplayStateEnum>,
     heat_tx: SyncStateChannelSender<'a, SyncHeatStateEnum>,
     storage_tx: SyncStateChannelSender<'a, SyncStorageStateEnum>,
+    buzzer_tx: SyncStateChannelSender<'a, SyncBuzzerStateEnum>,
     target_temp: (u16, bool),
     profile: (temperature::TemperatureProfileEnum, bool),
+    manual_duty: u8,
     pid: (bool, bool),
     pid_p: (f32, bool),
     pid_i: (f32, bool),
     pid_d: (f32, bool),
+    pid_i_min: (f32, bool),
+    pid_i_max: (f32, bool),
+    pid_schedule: (bool, bool),
+    pid_bands: ([PidBand; PID_BANDS], bool),
+    bang_below: (f32, bool),
+    bang_above: (f32, bool),
+    bang_min_switch: (f32, bool),
+    pid_band_edit: usize,
     pid_autotune_inprogress: PidAutoTuneInProgressEnum,
     pid_autotune_iteration: u8,
+    autotune_amplitude: (f32, bool),
+    autotune_hysteresis: (f32, bool),
+    autotune_cycles: (u8, bool),
+    autotune_rule: (TuningRuleEnum, bool),
+    autotune_result: Option<RelayResult>,
+    autotune_compare: bool,
+    autotune_sweep: bool,
+    autotune_band: usize,
+    autotune_results: [Option<RelayResult>; PID_BANDS],
+    characterize_inprogress: PidAutoTuneInProgressEnum,
+    characterize_elapsed: u16,
+    characterize_fit: bool,
+    characterize_duty: (f32, bool),
+    imc_lambda: (f32, bool),
+    plant_model: (PlantModel, bool),
+    feedforward: (bool, bool),
+    feedforward_learn: (bool, bool),
+    duty_map: (DutyMap, bool),
     temp_wait_time: (f32, bool),
     temp_extra_time: (f32, bool),
     temp_offset: (i16, bool),
     temp_lead_offset: (i16, bool),
+    lag_profile_a: (LagCompensationEnum, bool),
+    plate_sensor: (SensorTypeEnum, bool),
+    board_probe: (bool, bool),
+    sync_source: (SyncSourceEnum, bool),
+    output_mode: (OutputModeEnum, bool),
+    output_window: (f32, bool),
+    output_min_on: (f32, bool),
+    output_min_off: (f32, bool),
+    max_duty: (f32, bool),
+    max_duty_soak: (f32, bool),
+    soft_start: (f32, bool),
+    control_period: (u16, bool),
+    fan_mode: (FanModeEnum, bool),
+    fan_cooling_rate: (f32, bool),
+    buzzer: (bool, bool),
+    post_pulse: (bool, bool),
+    idle_timeout: (u16, bool),
+    manual_temp_max: (u16, bool),
+    manual_time_max: (u16, bool),
+    heater_watts: (u16, bool),
+    zones: ([ZoneSettings; AUX_ZONES], bool),
+    zone_edit: usize,
+    power_budget: (u16, bool),
+    power_policy: (AllocPolicyEnum, bool),
+    interlocks: ([InterlockSettings; INTERLOCKS], bool),
+    interlock_edit: usize,
+    cascade: (bool, bool),
+    cascade_p: (f32, bool),
+    cascade_i: (f32, bool),
+    cascade_max_over: (u16, bool),
+    die_temp: f32,
+    vsys: f32,
+    jitter: JitterReport,
+    post_result: Option<PostResultEnum>,
+    stats: StatsData,
+    stats_reset: bool,
+    run_updates_pending: bool,
 }
 
 impl<'a> Menu<'a> {
@@ -590,24 +3547,140 @@ impl<'a> Menu<'a> {
             display_tx: channels.get_display_tx(),
             heat_tx: channels.get_heat_tx(),
             storage_tx: channels.get_storage_tx(),
+            buzzer_tx: channels.get_buzzer_tx(),
             target_temp: (0, false),
             profile: (temperature::TemperatureProfileEnum::Static, false),
+            manual_duty: 0,
             pid: (startup_storage.pid, false),
             pid_p: (startup_storage.pid_p, false),
             pid_i: (startup_storage.pid_i, false),
             pid_d: (startup_storage.pid_d, false),
+            pid_i_min: (startup_storage.pid_i_min, false),
+            pid_i_max: (startup_storage.pid_i_max, false),
+            pid_schedule: (startup_storage.pid_schedule, false),
+            pid_bands: (startup_storage.pid_bands, false),
+            bang_below: (startup_storage.bang_below, false),
+            bang_above: (startup_storage.bang_above, false),
+            bang_min_switch: (startup_storage.bang_min_switch, false),
+            pid_band_edit: 0,
             pid_autotune_inprogress: PidAutoTuneInProgressEnum::Idle,
             pid_autotune_iteration: 0,
+            autotune_amplitude: (startup_storage.autotune_amplitude, false),
+            autotune_hysteresis: (startup_storage.autotune_hysteresis, false),
+            autotune_cycles: (startup_storage.autotune_cycles, false),
+            autotune_rule: (startup_storage.autotune_rule, false),
+            autotune_result: None,
+            autotune_compare: false,
+            autotune_sweep: false,
+            autotune_band: 0,
+            autotune_results: [None; PID_BANDS],
+            characterize_inprogress: PidAutoTuneInProgressEnum::Idle,
+            characterize_elapsed: 0,
+            characterize_fit: false,
+            characterize_duty: (startup_storage.characterize_duty, false),
+            imc_lambda: (startup_storage.imc_lambda, false),
+            plant_model: (startup_storage.plant_model, false),
+            feedforward: (startup_storage.feedforward, false),
+            feedforward_learn: (startup_storage.feedforward_learn, false),
+            duty_map: (startup_storage.duty_map, false),
             temp_wait_time: (startup_storage.temp_wait_time, false),
             temp_extra_time: (startup_storage.temp_extra_time, false),
             temp_offset: (startup_storage.temp_offset, false),
             temp_lead_offset: (startup_storage.temp_lead_offset, false),
+            lag_profile_a: (startup_storage.lag_profile_a, false),
+            plate_sensor: (startup_storage.plate_sensor, false),
+            board_probe: (startup_storage.board_probe, false),
+            sync_source: (startup_storage.sync_source, false),
+            output_mode: (startup_storage.output_mode, false),
+            output_window: (startup_storage.output_window, false),
+            output_min_on: (startup_storage.output_min_on, false),
+            output_min_off: (startup_storage.output_min_off, false),
+            max_duty: (startup_storage.max_duty, false),
+            max_duty_soak: (startup_storage.max_duty_soak, false),
+            soft_start: (startup_storage.soft_start, false),
+            control_period: (startup_storage.control_period, false),
+            fan_mode: (startup_storage.fan_mode, false),
+            fan_cooling_rate: (startup_storage.fan_cooling_rate, false),
+            buzzer: (startup_storage.buzzer, false),
+            post_pulse: (startup_storage.post_pulse, false),
+            idle_timeout: (startup_storage.idle_timeout, false),
+            manual_temp_max: (startup_storage.manual_temp_max, false),
+            manual_time_max: (startup_storage.manual_time_max, false),
+            heater_watts: (startup_storage.heater_watts, false),
+            zones: (startup_storage.zones, false),
+            zone_edit: 0,
+            power_budget: (startup_storage.power_budget, false),
+            power_policy: (startup_storage.power_policy, false),
+            interlocks: (startup_storage.interlocks, false),
+            interlock_edit: 0,
+            cascade: (startup_storage.cascade, false),
+            cascade_p: (startup_storage.cascade_p, false),
+            cascade_i: (startup_storage.cascade_i, false),
+            cascade_max_over: (startup_storage.cascade_max_over, false),
+            die_temp: 0.0,
+            vsys: 0.0,
+            jitter: JitterReport::default(),
+            post_result: None,
+            stats: StatsData::default(),
+            stats_reset: false,
+            run_updates_pending: false,
+        }
+    }
+
+    /**
+    ### Gains from autotune result with selected rule
+    * Not applied until accepted in review
+    */
+    fn autotune_gains(&self) -> (f32, f32, f32) {
+        match &self.autotune_result {
+            Some(result) => self.rule_gains(result),
+            None => (0.0, 0.0, 0.0),
+        }
+    }
+
+    fn rule_gains(&self, result: &RelayResult) -> (f32, f32, f32) {
+        if result.tu > 0.0 {
+            self.autotune_rule.0.gains(result.ku, result.tu)
+        } else {
+            (0.0, 0.0, 0.0)
+        }
+    }
+
+    /**
+    ### Stored gains replaced by accepting the autotune result
+    * Band gains when tuning bands, base gains otherwise
+    */
+    fn autotune_stored_gains(&self) -> (f32, f32, f32) {
+        if self.autotune_sweep {
+            let band = &self.pid_bands.0[self.autotune_band];
+            (band.p, band.i, band.d)
+        } else {
+            (self.pid_p.0, self.pid_i.0, self.pid_d.0)
+        }
+    }
+
+    fn imc_gains(&self) -> (f32, f32, f32) {
+        self.plant_model.0.imc_gains(self.imc_lambda.0)
+    }
+
+    /**
+    ### Menu rows on display
+    * Last row is taken by zone line while any auxiliary zone is enabled
+    */
+    fn visible_items(&self) -> usize {
+        if self.zones.0.iter().any(|zone| zone.enabled) {
+            MENU_VISIBLE_ITEMS - 1
+        } else {
+            MENU_VISIBLE_ITEMS
         }
     }
 
     pub fn render(&self) -> StaticString<100> {
         let mut output = StaticString::default();
-        for pos in 0..self.menu.len() {
+        let visible = self.visible_items();
+        let first = (self.position as usize).saturating_sub(visible - 1);
+        let last = min(first + visible, self.menu.len());
+        for pos in first..last {
             let item = &self.menu[pos];
             if self.position == pos as u8 {
                 match item.action {
@@ -712,12 +3785,51 @@ impl<'a> Menu<'a> {
         }
     }
 
+    /**
+    ### Sends run target to heater without blocking
+    * For heater messages, a blocking send could wait on a heater that waits on menu
+    * Retried on next button poll while heater channel is full
+    * Other changed values, e.g. characterized model, go out with next `send_updates`
+    */
+    fn send_run_updates(&mut self) {
+        self.run_updates_pending = false;
+        if !self.target_temp.1 && !self.profile.1 {
+            return;
+        }
+
+        if self
+            .heat_tx
+            .try_send(SyncHeatStateEnum::TargetTemp(
+                self.target_temp.0,
+                self.profile.0.clone(),
+            ))
+            .is_err()
+        {
+            self.run_updates_pending = true;
+            return;
+        }
+        if self
+            .display_tx
+            .try_send(SyncDisplayStateEnum::PeakTargetTemp(
+                self.target_temp.0,
+                self.profile.0.clone(),
+            ))
+            .is_err()
+        {
+            //ignore: msg dropped
+        }
+        self.target_temp.1 = false;
+        self.profile.1 = false;
+    }
+
     async fn send_updates(
         &mut self,
         display_tx: SyncStateChannelSender<'a, SyncDisplayStateEnum>,
         heat_tx: SyncStateChannelSender<'a, SyncHeatStateEnum>,
         storage_tx: SyncStateChannelSender<'a, SyncStorageStateEnum>,
     ) {
+        //run target goes out below
+        self.run_updates_pending = false;
         if self.target_temp.1 || self.profile.1 {
             heat_tx
                 .send(SyncHeatStateEnum::TargetTemp(
@@ -752,6 +3864,53 @@ impl<'a> Menu<'a> {
                 .await;
         }
 
+        if self.pid_i_min.1 || self.pid_i_max.1 {
+            heat_tx
+                .send(SyncHeatStateEnum::PidLimits {
+                    i_min: self.pid_i_min.0,
+                    i_max: self.pid_i_max.0,
+                })
+                .await;
+            storage_tx
+                .send(SyncStorageStateEnum::WritePidLimits {
+                    i_min: self.pid_i_min.0,
+                    i_max: self.pid_i_max.0,
+                })
+                .await;
+        }
+
+        if self.bang_below.1 || self.bang_above.1 || self.bang_min_switch.1 {
+            heat_tx
+                .send(SyncHeatStateEnum::BangBang {
+                    below: self.bang_below.0,
+                    above: self.bang_above.0,
+                    min_switch: self.bang_min_switch.0,
+                })
+                .await;
+            storage_tx
+                .send(SyncStorageStateEnum::WriteBangBang {
+                    below: self.bang_below.0,
+                    above: self.bang_above.0,
+                    min_switch: self.bang_min_switch.0,
+                })
+                .await;
+        }
+
+        if self.pid_schedule.1 || self.pid_bands.1 {
+            heat_tx
+                .send(SyncHeatStateEnum::PidSchedule {
+                    schedule: self.pid_schedule.0,
+                    bands: self.pid_bands.0,
+                })
+                .await;
+            storage_tx
+                .send(SyncStorageStateEnum::WritePidSchedule {
+                    schedule: self.pid_schedule.0,
+                    bands: self.pid_bands.0,
+                })
+                .await;
+        }
+
         if self.temp_wait_time.1
             || self.temp_extra_time.1
             || self.temp_offset.1
@@ -775,15 +3934,360 @@ impl<'a> Menu<'a> {
                 .await;
         }
 
+        if self.lag_profile_a.1 {
+            heat_tx
+                .send(SyncHeatStateEnum::LagCompensation {
+                    profile_a: self.lag_profile_a.0,
+                })
+                .await;
+            storage_tx
+                .send(SyncStorageStateEnum::WriteLagCompensation {
+                    profile_a: self.lag_profile_a.0,
+                })
+                .await;
+        }
+
+        if self.plate_sensor.1 {
+            //applied on next boot
+            storage_tx
+                .send(SyncStorageStateEnum::WriteSensor {
+                    plate_sensor: self.plate_sensor.0,
+                })
+                .await;
+        }
+
+        if self.board_probe.1 || self.sync_source.1 {
+            heat_tx
+                .send(SyncHeatStateEnum::BoardProbe {
+                    enabled: self.board_probe.0,
+                    sync_source: self.sync_source.0,
+                })
+                .await;
+            storage_tx
+                .send(SyncStorageStateEnum::WriteBoardProbe {
+                    board_probe: self.board_probe.0,
+                    sync_source: self.sync_source.0,
+                })
+                .await;
+        }
+
+        if self.output_mode.1
+            || self.output_window.1
+            || self.output_min_on.1
+            || self.output_min_off.1
+        {
+            heat_tx
+                .send(SyncHeatStateEnum::Output {
+                    mode: self.output_mode.0,
+                    window: self.output_window.0,
+                    min_on: self.output_min_on.0,
+                    min_off: self.output_min_off.0,
+                })
+                .await;
+            storage_tx
+                .send(SyncStorageStateEnum::WriteOutput {
+                    mode: self.output_mode.0,
+                    window: self.output_window.0,
+                    min_on: self.output_min_on.0,
+                    min_off: self.output_min_off.0,
+                })
+                .await;
+        }
+
+        if self.max_duty.1 || self.max_duty_soak.1 || self.soft_start.1 {
+            heat_tx
+                .send(SyncHeatStateEnum::PowerLimit {
+                    max_duty: self.max_duty.0,
+                    max_duty_soak: self.max_duty_soak.0,
+                    soft_start: self.soft_start.0,
+                })
+                .await;
+            storage_tx
+                .send(SyncStorageStateEnum::WritePowerLimit {
+                    max_duty: self.max_duty.0,
+                    max_duty_soak: self.max_duty_soak.0,
+                    soft_start: self.soft_start.0,
+                })
+                .await;
+        }
+
+        if self.control_period.1 {
+            heat_tx
+                .send(SyncHeatStateEnum::ControlPeriod(self.control_period.0))
+                .await;
+            storage_tx
+                .send(SyncStorageStateEnum::WriteControlPeriod {
+                    period: self.control_period.0,
+                })
+                .await;
+        }
+
+        if self.fan_mode.1 || self.fan_cooling_rate.1 {
+            heat_tx
+                .send(SyncHeatStateEnum::Fan {
+                    mode: self.fan_mode.0,
+                    cooling_rate: self.fan_cooling_rate.0,
+                })
+                .await;
+            storage_tx
+                .send(SyncStorageStateEnum::WriteFan {
+                    mode: self.fan_mode.0,
+                    cooling_rate: self.fan_cooling_rate.0,
+                })
+                .await;
+        }
+
+        if self.buzzer.1 {
+            self.buzzer_tx
+                .send(SyncBuzzerStateEnum::Enabled(self.buzzer.0))
+                .await;
+            storage_tx
+                .send(SyncStorageStateEnum::WriteBuzzer {
+                    enabled: self.buzzer.0,
+                })
+                .await;
+        }
+
+        if self.zones.1 {
+            for (index, settings) in self.zones.0.iter().enumerate() {
+                heat_tx
+                    .send(SyncHeatStateEnum::Zone {
+                        index,
+                        settings: *settings,
+                    })
+                    .await;
+                storage_tx
+                    .send(SyncStorageStateEnum::WriteZone {
+                        index,
+                        settings: *settings,
+                    })
+                    .await;
+            }
+        }
+
+        if self.interlocks.1 {
+            for (index, settings) in self.interlocks.0.iter().enumerate() {
+                heat_tx
+                    .send(SyncHeatStateEnum::Interlock {
+                        index,
+                        settings: *settings,
+                    })
+                    .await;
+                storage_tx
+                    .send(SyncStorageStateEnum::WriteInterlock {
+                        index,
+                        settings: *settings,
+                    })
+                    .await;
+            }
+        }
+
+        if self.manual_temp_max.1 || self.manual_time_max.1 {
+            heat_tx
+                .send(SyncHeatStateEnum::ManualLimits {
+                    temp_max: self.manual_temp_max.0,
+                    time_max: self.manual_time_max.0,
+                })
+                .await;
+            storage_tx
+                .send(SyncStorageStateEnum::WriteManualLimits {
+                    temp_max: self.manual_temp_max.0,
+                    time_max: self.manual_time_max.0,
+                })
+                .await;
+        }
+
+        if self.power_budget.1 || self.power_policy.1 {
+            heat_tx
+                .send(SyncHeatStateEnum::PowerBudget {
+                    budget: self.power_budget.0,
+                    policy: self.power_policy.0,
+                })
+                .await;
+            storage_tx
+                .send(SyncStorageStateEnum::WritePowerBudget {
+                    budget: self.power_budget.0,
+                    policy: self.power_policy.0,
+                })
+                .await;
+        }
+
+        if self.heater_watts.1 {
+            heat_tx
+                .send(SyncHeatStateEnum::HeaterWatts(self.heater_watts.0))
+                .await;
+            storage_tx
+                .send(SyncStorageStateEnum::WriteHeaterWatts {
+                    watts: self.heater_watts.0,
+                })
+                .await;
+        }
+
+        if self.stats_reset {
+            //heater owns counters and writes them
+            heat_tx.send(SyncHeatStateEnum::StatsReset).await;
+        }
+
+        if self.idle_timeout.1 {
+            heat_tx
+                .send(SyncHeatStateEnum::IdleTimeout(self.idle_timeout.0))
+                .await;
+            storage_tx
+                .send(SyncStorageStateEnum::WriteIdleTimeout {
+                    timeout: self.idle_timeout.0,
+                })
+                .await;
+        }
+
+        if self.post_pulse.1 {
+            //applied on next boot
+            storage_tx
+                .send(SyncStorageStateEnum::WritePost {
+                    pulse: self.post_pulse.0,
+                })
+                .await;
+        }
+
+        if self.cascade.1 || self.cascade_p.1 || self.cascade_i.1 || self.cascade_max_over.1 {
+            heat_tx
+                .send(SyncHeatStateEnum::Cascade {
+                    cascade: self.cascade.0,
+                    cascade_p: self.cascade_p.0,
+                    cascade_i: self.cascade_i.0,
+                    cascade_max_over: self.cascade_max_over.0,
+                })
+                .await;
+            storage_tx
+                .send(SyncStorageStateEnum::WriteCascade {
+                    cascade: self.cascade.0,
+                    cascade_p: self.cascade_p.0,
+                    cascade_i: self.cascade_i.0,
+                    cascade_max_over: self.cascade_max_over.0,
+                })
+                .await;
+        }
+
+        if self.autotune_amplitude.1
+            || self.autotune_hysteresis.1
+            || self.autotune_cycles.1
+            || self.autotune_rule.1
+        {
+            heat_tx
+                .send(SyncHeatStateEnum::AutoTuneSettings {
+                    amplitude: self.autotune_amplitude.0,
+                    hysteresis: self.autotune_hysteresis.0,
+                    cycles: self.autotune_cycles.0,
+                })
+                .await;
+            storage_tx
+                .send(SyncStorageStateEnum::WriteAutoTuneSettings {
+                    amplitude: self.autotune_amplitude.0,
+                    hysteresis: self.autotune_hysteresis.0,
+                    cycles: self.autotune_cycles.0,
+                    rule: self.autotune_rule.0,
+                })
+                .await;
+        }
+
+        if self.characterize_duty.1 || self.imc_lambda.1 {
+            heat_tx
+                .send(SyncHeatStateEnum::CharacterizeSettings {
+                    duty: self.characterize_duty.0,
+                    lambda: self.imc_lambda.0,
+                })
+                .await;
+            storage_tx
+                .send(SyncStorageStateEnum::WriteCharacterizeSettings {
+                    duty: self.characterize_duty.0,
+                    lambda: self.imc_lambda.0,
+                })
+                .await;
+        }
+
+        if self.plant_model.1 {
+            heat_tx
+                .send(SyncHeatStateEnum::PlantModel(self.plant_model.0))
+                .await;
+            storage_tx
+                .send(SyncStorageStateEnum::WritePlantModel {
+                    model: self.plant_model.0,
+                })
+                .await;
+        }
+
+        if self.feedforward.1 || self.feedforward_learn.1 || self.duty_map.1 {
+            heat_tx
+                .send(SyncHeatStateEnum::FeedForward {
+                    enabled: self.feedforward.0,
+                    learn: self.feedforward_learn.0,
+                    map: self.duty_map.0,
+                })
+                .await;
+            storage_tx
+                .send(SyncStorageStateEnum::WriteFeedForward {
+                    enabled: self.feedforward.0,
+                    learn: self.feedforward_learn.0,
+                    map: self.duty_map.0,
+                })
+                .await;
+        }
+
         self.target_temp.1 = false;
         self.profile.1 = false;
+        self.pid.1 = false;
         self.pid_p.1 = false;
         self.pid_i.1 = false;
         self.pid_d.1 = false;
+        self.pid_i_min.1 = false;
+        self.pid_i_max.1 = false;
+        self.pid_schedule.1 = false;
+        self.pid_bands.1 = false;
+        self.bang_below.1 = false;
+        self.bang_above.1 = false;
+        self.bang_min_switch.1 = false;
         self.temp_wait_time.1 = false;
         self.temp_extra_time.1 = false;
         self.temp_offset.1 = false;
         self.temp_lead_offset.1 = false;
+        self.lag_profile_a.1 = false;
+        self.plate_sensor.1 = false;
+        self.board_probe.1 = false;
+        self.sync_source.1 = false;
+        self.output_mode.1 = false;
+        self.output_window.1 = false;
+        self.output_min_on.1 = false;
+        self.output_min_off.1 = false;
+        self.max_duty.1 = false;
+        self.max_duty_soak.1 = false;
+        self.soft_start.1 = false;
+        self.control_period.1 = false;
+        self.fan_mode.1 = false;
+        self.fan_cooling_rate.1 = false;
+        self.buzzer.1 = false;
+        self.post_pulse.1 = false;
+        self.idle_timeout.1 = false;
+        self.manual_temp_max.1 = false;
+        self.manual_time_max.1 = false;
+        self.heater_watts.1 = false;
+        self.zones.1 = false;
+        self.power_budget.1 = false;
+        self.power_policy.1 = false;
+        self.interlocks.1 = false;
+        self.stats_reset = false;
+        self.cascade.1 = false;
+        self.cascade_p.1 = false;
+        self.cascade_i.1 = false;
+        self.cascade_max_over.1 = false;
+        self.autotune_amplitude.1 = false;
+        self.autotune_hysteresis.1 = false;
+        self.autotune_cycles.1 = false;
+        self.autotune_rule.1 = false;
+        self.characterize_duty.1 = false;
+        self.imc_lambda.1 = false;
+        self.plant_model.1 = false;
+        self.feedforward.1 = false;
+        self.feedforward_learn.1 = false;
+        self.duty_map.1 = false;
     }
 
     pub async fn btn_task(&mut self) -> ! {
@@ -883,32 +4387,104 @@ impl<'a> Menu<'a> {
                     match msg {
                         SyncMenuStateEnum::PidAutoTune {
                             iteration,
-                            pid_p,
-                            pid_i,
-                            pid_d,
+                            result,
                             done,
                         } => {
+                            //results are reviewed before persisting
                             self.pid_autotune_iteration = iteration;
-                            self.pid_p = (pid_p, true);
-                            self.pid_i = (pid_i, true);
-                            self.pid_d = (pid_d, true);
-                            if done {
-                                self.pid = (true, true);
+                            self.autotune_result = Some(result);
+                            if self.autotune_sweep {
+                                self.autotune_results[self.autotune_band] = Some(result);
+                            }
+                            if done && self.autotune_sweep && self.autotune_band + 1 < PID_BANDS {
+                                //next band setpoint in the same session
+                                self.autotune_band += 1;
+                                self.target_temp =
+                                    (self.pid_bands.0[self.autotune_band].temp, true);
+                                self.profile = (
+                                    TemperatureProfileEnum::AutoCalibrate {
+                                        state: Default::default(),
+                                    },
+                                    true,
+                                );
+                            } else if done {
+                                if self.autotune_sweep {
+                                    self.autotune_band = 0;
+                                    self.autotune_result = self.autotune_results[0];
+                                }
+                                self.autotune_compare = false;
                                 self.profile = (TemperatureProfileEnum::Static, true);
                                 self.target_temp = (0, true);
                                 self.pid_autotune_inprogress = PidAutoTuneInProgressEnum::Done;
                             }
+                            self.send_run_updates();
+                        }
+                        SyncMenuStateEnum::Characterize {
+                            elapsed,
+                            model,
+                            done,
+                        } => {
+                            self.characterize_elapsed = elapsed;
+                            if done {
+                                //model is persisted, gains are applied by user
+                                self.characterize_fit = model.is_some();
+                                if let Some(model) = model {
+                                    self.plant_model = (model, true);
+                                }
+                                self.profile = (TemperatureProfileEnum::Static, true);
+                                self.target_temp = (0, true);
+                                self.characterize_inprogress = PidAutoTuneInProgressEnum::Done;
+                                self.send_run_updates();
+                            }
+                        }
+                        SyncMenuStateEnum::DutyMap(map) => {
+                            //learned by heater during holds
+                            self.duty_map = (map, true);
+                            self.send_updates(self.display_tx, self.heat_tx, self.storage_tx)
+                                .await;
+                        }
+                        SyncMenuStateEnum::Diagnostics { die_temp, vsys } => {
+                            self.die_temp = die_temp;
+                            self.vsys = vsys;
+                        }
+                        SyncMenuStateEnum::LoopJitter(report) => {
+                            self.jitter = report;
+                        }
+                        SyncMenuStateEnum::Post(result) => {
+                            self.post_result = Some(result);
+                        }
+                        SyncMenuStateEnum::Stats(stats) => {
+                            self.stats = stats;
+                        }
+                        SyncMenuStateEnum::IdleOff | SyncMenuStateEnum::InterlockAbort => {
+                            //heater already dropped target
+                            self.target_temp = (0, true);
+                            self.send_run_updates();
+                        }
+                        SyncMenuStateEnum::IdentifyRefused => {
+                            //max duty was lowered below test duty
+                            self.pid_autotune_inprogress = PidAutoTuneInProgressEnum::Idle;
+                            self.characterize_inprogress = PidAutoTuneInProgressEnum::Idle;
+                            self.profile = (TemperatureProfileEnum::Static, true);
+                            self.target_temp = (0, true);
+                            self.send_run_updates();
                         }
                     };
-                    self.send_updates(self.display_tx, self.heat_tx, self.storage_tx)
-                        .await;
                     4
                 }
             };
 
             Timer::at(debounce).await;
 
-            last_action = action;
+            //heater message updates left over from a full channel
+            if self.run_updates_pending {
+                self.send_run_updates();
+            }
+
+            //msg refresh does not interrupt button debounce
+            if action != 4 {
+                last_action = action;
+            }
 
             let amount = match delay {
                 9.. => 1,
@@ -918,6 +4494,13 @@ impl<'a> Menu<'a> {
                 0.. => 20,
             };
 
+            //buttons reset heater idle timer
+            if (1..=3).contains(&action)
+                && self.heat_tx.try_send(SyncHeatStateEnum::Activity).is_err()
+            {
+                //ignore: msg dropped
+            }
+
             match action {
                 1 => self.on_up(amount),
                 2 => {
@@ -0,0 +1,109 @@
+use embassy_rp::adc::Channel;
+use embassy_time::{Duration, Instant};
+
+use crate::sampling::{Sampler, SAMPLES_DEFAULT};
+use crate::tools::SharedAdc;
+
+const ADC_VREF: f32 = 3.3;
+const ADC_MAX: f32 = 4096.0;
+const VSYS_DIVIDER: f32 = 3.0;
+const DIE_TEMP_V27: f32 = 0.706;
+const DIE_TEMP_SLOPE: f32 = 0.001721;
+
+const SAMPLE_PERIOD: Duration = Duration::from_millis(1000);
+const DIE_TEMP_MAX: f32 = 70.0;
+const VSYS_MIN: f32 = 4.3;
+const FAULT_COUNT: u8 = 3;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct SystemStatus {
+    pub die_temp: f32,
+    pub vsys: f32,
+}
+
+/**
+### Controller health monitor
+* RP2040 internal temperature sensor (ADC4)
+* VSYS/3 on ADC3 (Pico)
+* Panics when enclosure overheats or supply sags, sensor readings and gate drive are not reliable then
+*/
+pub(crate) struct SystemMonitor<'a> {
+    adc: &'a SharedAdc<'a>,
+    temp_ch: Channel<'a>,
+    vsys_ch: Channel<'a>,
+    sampler: Sampler<SAMPLES_DEFAULT>,
+    last_sample: Instant,
+    die_temp_faults: u8,
+    vsys_faults: u8,
+}
+
+impl<'a> SystemMonitor<'a> {
+    pub fn new(adc: &'a SharedAdc<'a>, temp_ch: Channel<'a>, vsys_ch: Channel<'a>) -> Self {
+        Self {
+            adc,
+            temp_ch,
+            vsys_ch,
+            sampler: Sampler::new(),
+            last_sample: Instant::now(),
+            die_temp_faults: 0,
+            vsys_faults: 0,
+        }
+    }
+
+    /**
+    ### Samples if period elapsed
+    * Returns `None` if not sampled
+    */
+    pub async fn poll(&mut self) -> Option<SystemStatus> {
+        if self.last_sample.elapsed() < SAMPLE_PERIOD {
+            return None;
+        }
+
+        Some(self.sample().await)
+    }
+
+    pub async fn sample(&mut self) -> SystemStatus {
+        self.last_sample = Instant::now();
+
+        let (temp_stats, vsys_stats) = {
+            let mut adc = self.adc.lock().await;
+            let temp_stats = self
+                .sampler
+                .sample(&mut adc, &mut self.temp_ch)
+                .await
+                .expect("monitor: temp fail");
+            let vsys_stats = self
+                .sampler
+                .sample(&mut adc, &mut self.vsys_ch)
+                .await
+                .expect("monitor: vsys fail");
+            (temp_stats, vsys_stats)
+        };
+
+        let die_v = temp_stats.mean * ADC_VREF / ADC_MAX;
+        let status = SystemStatus {
+            die_temp: 27.0 - (die_v - DIE_TEMP_V27) / DIE_TEMP_SLOPE,
+            vsys: vsys_stats.mean * ADC_VREF / ADC_MAX * VSYS_DIVIDER,
+        };
+
+        if status.die_temp > DIE_TEMP_MAX {
+            self.die_temp_faults += 1;
+            if self.die_temp_faults >= FAULT_COUNT {
+                panic!("Enclosure overheat\nmcu: {:.1}C!", status.die_temp);
+            }
+        } else {
+            self.die_temp_faults = 0;
+        }
+
+        if status.vsys < VSYS_MIN {
+            self.vsys_faults += 1;
+            if self.vsys_faults >= FAULT_COUNT {
+                panic!("Supply undervolt\nvsys: {:.2}V!", status.vsys);
+            }
+        } else {
+            self.vsys_faults = 0;
+        }
+
+        status
+    }
+}
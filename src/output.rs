@@ -0,0 +1,47 @@
+use bincode::{Decode, Encode};
+use embassy_time::Instant;
+use reflow_plate_logic::output::TimeProportional;
+
+pub(crate) use reflow_plate_logic::output::PowerLimit;
+
+#[derive(Debug, Clone, Copy, PartialEq, Encode, Decode)]
+pub enum OutputModeEnum {
+    Pwm,
+    TimeProportional,
+}
+
+/**
+### Heater output stage
+* Converts controller demand 0..1 into mosfet compare value
+* Pwm: demand is written as duty, for DC mosfet
+* TimeProportional: demand sets on time of a slow window, for zero-cross SSR
+*/
+pub(crate) struct Output {
+    mode: OutputModeEnum,
+    window: TimeProportional,
+}
+
+impl Output {
+    pub fn new(mode: OutputModeEnum, window: f32, min_on: f32, min_off: f32) -> Self {
+        Self {
+            mode,
+            window: TimeProportional::new(window, min_on, min_off),
+        }
+    }
+
+    pub fn set_phase(&mut self, phase: f32) {
+        self.window.set_phase(phase);
+    }
+
+    pub fn set_settings(&mut self, mode: OutputModeEnum, window: f32, min_on: f32, min_off: f32) {
+        self.mode = mode;
+        self.window.set_settings(window, min_on, min_off);
+    }
+
+    pub fn compare(&mut self, demand: f32, top: u16) -> u16 {
+        match self.mode {
+            OutputModeEnum::Pwm => (demand.clamp(0.0, 1.0) * top as f32) as u16,
+            OutputModeEnum::TimeProportional => self.window.compare_at(demand, top, Instant::now()),
+        }
+    }
+}
@@ -0,0 +1,217 @@
+use bincode::{Decode, Encode};
+use micromath::F32Ext;
+
+const STEP_SAMPLES: usize = 64;
+const STEP_INTERVAL: f32 = 1.0;
+const STEP_TIME_MAX: f32 = 1800.0;
+const STEADY_WINDOW: f32 = 60.0;
+const STEADY_RISE: f32 = 0.5;
+const FIT_RISE_MIN: f32 = 5.0;
+const FIT_FIRST_POINT: f32 = 0.2;
+
+/**
+### First-order-plus-dead-time plate model
+* `gain`: steady-state rise in °C per % duty
+* `tau`: time constant in s
+* `dead_time`: delay before the plate responds in s
+* `ambient`: temperature the step started from
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Default, Encode, Decode)]
+pub(crate) struct PlantModel {
+    pub gain: f32,
+    pub tau: f32,
+    pub dead_time: f32,
+    pub ambient: f32,
+}
+
+impl PlantModel {
+    pub fn valid(&self) -> bool {
+        self.gain > 0.0 && self.tau > 0.0
+    }
+
+    /**
+    ### IMC (lambda) PID tuning
+    * Closed loop time constant is `lambda_factor * tau`, never faster than dead time
+    * Returns (kp, ki, kd) for output in duty 0..1
+    */
+    pub fn imc_gains(&self, lambda_factor: f32) -> (f32, f32, f32) {
+        if !self.valid() {
+            return (0.0, 0.0, 0.0);
+        }
+
+        let gain = self.gain * 100.0;
+        let lambda = (lambda_factor * self.tau).max(self.dead_time);
+        let half_dead = self.dead_time * 0.5;
+
+        let kp = (self.tau + half_dead) / (gain * (lambda + half_dead));
+        let ti = self.tau + half_dead;
+        let td = self.tau * self.dead_time / (2.0 * self.tau + self.dead_time);
+
+        (kp, kp / ti, kp * td)
+    }
+}
+
+/**
+### Open-loop step response recorder
+* Samples are decimated when buffer fills, interval doubles
+* Finished when temperature settles, reaches limit or time runs out
+*/
+pub(crate) struct StepResponse {
+    duty: f32,
+    samples: [(f32, f32); STEP_SAMPLES],
+    count: usize,
+    interval: f32,
+    next_sample: f32,
+    start: Option<(f32, f32)>,
+    last: (f32, f32),
+    check: (f32, f32),
+}
+
+impl StepResponse {
+    pub fn new(duty: f32) -> Self {
+        Self {
+            duty,
+            samples: [(0.0, 0.0); STEP_SAMPLES],
+            count: 0,
+            interval: STEP_INTERVAL,
+            next_sample: 0.0,
+            start: None,
+            last: (0.0, 0.0),
+            check: (0.0, 0.0),
+        }
+    }
+
+    pub fn set_duty(&mut self, duty: f32) {
+        self.duty = duty;
+    }
+
+    pub fn duty(&self) -> f32 {
+        self.duty
+    }
+
+    pub fn reset(&mut self) {
+        self.count = 0;
+        self.interval = STEP_INTERVAL;
+        self.next_sample = 0.0;
+        self.start = None;
+        self.last = (0.0, 0.0);
+        self.check = (0.0, 0.0);
+    }
+
+    pub fn elapsed(&self) -> f32 {
+        self.last.0
+    }
+
+    /**
+    ### Records sample
+    * Returns true when response is finished
+    */
+    pub fn record(&mut self, time: f32, temp: f32, limit: f32) -> bool {
+        let (start_time, start_temp) = *self.start.get_or_insert((time, temp));
+        let t = time - start_time;
+        self.last = (t, temp);
+
+        if t >= self.next_sample {
+            if self.count == STEP_SAMPLES {
+                for i in 0..STEP_SAMPLES / 2 {
+                    self.samples[i] = self.samples[i * 2];
+                }
+                self.count = STEP_SAMPLES / 2;
+                self.interval *= 2.0;
+            }
+            self.samples[self.count] = (t, temp);
+            self.count += 1;
+            self.next_sample += self.interval;
+        }
+
+        if t - self.check.0 >= STEADY_WINDOW {
+            let settled = temp - self.check.1 < STEADY_RISE && temp - start_temp > FIT_RISE_MIN;
+            self.check = (t, temp);
+            if settled {
+                return true;
+            }
+        }
+
+        temp >= limit || t >= STEP_TIME_MAX
+    }
+
+    fn points(&self) -> impl Iterator<Item = (f32, f32)> + '_ {
+        self.samples[..self.count]
+            .iter()
+            .copied()
+            .chain(core::iter::once(self.last))
+    }
+
+    fn time_at(&self, temp: f32) -> Option<f32> {
+        let mut prev: Option<(f32, f32)> = None;
+        for (t, y) in self.points() {
+            if y >= temp {
+                return Some(match prev {
+                    Some((pt, py)) if y > py => pt + (t - pt) * (temp - py) / (y - py),
+                    _ => t,
+                });
+            }
+            prev = Some((t, y));
+        }
+        None
+    }
+
+    fn temp_at(&self, time: f32) -> f32 {
+        let mut prev: Option<(f32, f32)> = None;
+        for (t, y) in self.points() {
+            if t >= time {
+                return match prev {
+                    Some((pt, py)) if t > pt => py + (y - py) * (time - pt) / (t - pt),
+                    _ => y,
+                };
+            }
+            prev = Some((t, y));
+        }
+        self.last.1
+    }
+
+    /**
+    ### Fits FOPDT model
+    * Final value and time constant from three equally spaced points of the exponential,
+      steady state is not required
+    * Returns `None` if response is too small or not first-order like
+    */
+    pub fn fit(&self) -> Option<PlantModel> {
+        let (_, ambient) = self.start?;
+        let (t_end, y_end) = self.last;
+        let rise = y_end - ambient;
+        if rise < FIT_RISE_MIN || self.duty <= 0.0 {
+            return None;
+        }
+
+        let t1 = self.time_at(ambient + rise * FIT_FIRST_POINT)?;
+        let h = (t_end - t1) * 0.5;
+        if h <= 0.0 {
+            return None;
+        }
+
+        let d1 = self.temp_at(t1) - ambient;
+        let d2 = self.temp_at(t1 + h) - ambient;
+        let d3 = rise;
+        let den = d1 + d3 - 2.0 * d2;
+        if den >= 0.0 {
+            return None;
+        }
+
+        let final_rise = (d1 * d3 - d2 * d2) / den;
+        let ratio = (final_rise - d1) / (final_rise - d2);
+        if final_rise < d3 || ratio <= 1.0 {
+            return None;
+        }
+
+        let tau = h / ratio.ln();
+        let dead_time = (t1 - tau * (final_rise / (final_rise - d1)).ln()).max(0.0);
+
+        Some(PlantModel {
+            gain: final_rise / (self.duty * 100.0),
+            tau,
+            dead_time,
+            ambient,
+        })
+    }
+}
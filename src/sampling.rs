@@ -0,0 +1,71 @@
+use embassy_rp::adc::{Adc, Async, Channel, Error};
+use micromath::F32Ext;
+
+pub(crate) const SAMPLES_DEFAULT: usize = 16;
+const OUTLIER_LIMIT_DEFAULT: u16 = 24;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct SampleStats {
+    pub mean: f32,
+    pub std_dev: f32,
+}
+
+/**
+### Burst ADC sampler
+* Takes `N` readings back to back
+* Rejects readings farther than `outlier_limit` counts from the median
+* Averages the rest and reports their standard deviation
+*/
+pub(crate) struct Sampler<const N: usize> {
+    buf: [u16; N],
+    outlier_limit: u16,
+}
+
+impl<const N: usize> Sampler<N> {
+    pub fn new() -> Self {
+        Self {
+            buf: [0; N],
+            outlier_limit: OUTLIER_LIMIT_DEFAULT,
+        }
+    }
+
+    pub async fn sample(
+        &mut self,
+        adc: &mut Adc<'_, Async>,
+        ch: &mut Channel<'_>,
+    ) -> Result<SampleStats, Error> {
+        for val in &mut self.buf {
+            *val = adc.read(ch).await?;
+        }
+
+        Ok(self.filter())
+    }
+
+    fn filter(&mut self) -> SampleStats {
+        self.buf.sort_unstable();
+        let median = self.buf[N / 2];
+
+        let mut sum = 0u32;
+        let mut count = 0u32;
+        for val in self.buf {
+            if val.abs_diff(median) <= self.outlier_limit {
+                sum += val as u32;
+                count += 1;
+            }
+        }
+
+        //median is always accepted, count > 0
+        let mean = sum as f32 / count as f32;
+
+        let mut var = 0.0f32;
+        for val in self.buf {
+            if val.abs_diff(median) <= self.outlier_limit {
+                let diff = val as f32 - mean;
+                var += diff * diff;
+            }
+        }
+        let std_dev = (var / count as f32).sqrt();
+
+        SampleStats { mean, std_dev }
+    }
+}
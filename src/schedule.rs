@@ -0,0 +1,80 @@
+use bincode::{Decode, Encode};
+
+pub(crate) const PID_BANDS: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Encode, Decode)]
+pub(crate) struct PidBand {
+    pub temp: u16,
+    pub p: f32,
+    pub i: f32,
+    pub d: f32,
+    pub enabled: bool,
+}
+
+impl PidBand {
+    pub const fn new(temp: u16) -> Self {
+        Self {
+            temp,
+            p: 0.0,
+            i: 0.0,
+            d: 0.0,
+            enabled: false,
+        }
+    }
+}
+
+pub(crate) const PID_BANDS_DEFAULT: [PidBand; PID_BANDS] =
+    [PidBand::new(150), PidBand::new(200), PidBand::new(245)];
+
+/**
+### PID gain schedule
+* Each enabled band holds gains tuned at its temperature
+* Gains are interpolated linearly between neighbouring bands, held constant outside of them
+* Returns `None` when disabled or no band is enabled, base gains are used then
+*/
+pub(crate) struct GainSchedule {
+    enabled: bool,
+    bands: [PidBand; PID_BANDS],
+}
+
+impl GainSchedule {
+    pub fn new(enabled: bool, bands: [PidBand; PID_BANDS]) -> Self {
+        Self { enabled, bands }
+    }
+
+    pub fn set_bands(&mut self, enabled: bool, bands: [PidBand; PID_BANDS]) {
+        self.enabled = enabled;
+        self.bands = bands;
+    }
+
+    pub fn gains(&self, target: f32) -> Option<(f32, f32, f32)> {
+        if !self.enabled {
+            return None;
+        }
+
+        let mut lower: Option<&PidBand> = None;
+        let mut upper: Option<&PidBand> = None;
+        for band in self.bands.iter().filter(|x| x.enabled) {
+            if band.temp as f32 <= target {
+                if lower.map_or(true, |x| band.temp > x.temp) {
+                    lower = Some(band);
+                }
+            } else if upper.map_or(true, |x| band.temp < x.temp) {
+                upper = Some(band);
+            }
+        }
+
+        match (lower, upper) {
+            (Some(lower), Some(upper)) => {
+                let k = (target - lower.temp as f32) / (upper.temp - lower.temp) as f32;
+                Some((
+                    lower.p + (upper.p - lower.p) * k,
+                    lower.i + (upper.i - lower.i) * k,
+                    lower.d + (upper.d - lower.d) * k,
+                ))
+            }
+            (Some(band), None) | (None, Some(band)) => Some((band.p, band.i, band.d)),
+            (None, None) => None,
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use bincode::{Decode, Encode};
+use embassy_rp::adc::Channel;
+use embedded_hal_async::spi::SpiDevice;
+use micromath::F32Ext;
+
+use crate::sampling::{Sampler, SAMPLES_DEFAULT};
+use crate::thermistor::Thermistor;
+use crate::thermocouple::Thermocouple;
+use crate::tools::SharedAdc;
+
+pub(crate) use reflow_plate_logic::source::{
+    TemperatureReading, TemperatureSource, TemperatureSourceFault,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Encode, Decode)]
+pub(crate) enum SensorTypeEnum {
+    Thermistor,
+    Max31855,
+    Max6675,
+}
+
+pub(crate) struct ThermistorSource<'a> {
+    adc: &'a SharedAdc<'a>,
+    ch: Channel<'a>,
+    sampler: Sampler<SAMPLES_DEFAULT>,
+    thermistor: &'a Thermistor,
+}
+
+impl<'a> ThermistorSource<'a> {
+    pub fn new(adc: &'a SharedAdc<'a>, ch: Channel<'a>, thermistor: &'a Thermistor) -> Self {
+        Self {
+            adc,
+            ch,
+            sampler: Sampler::new(),
+            thermistor,
+        }
+    }
+}
+
+impl TemperatureSource for ThermistorSource<'_> {
+    async fn read(&mut self) -> Result<TemperatureReading, TemperatureSourceFault> {
+        let stats = {
+            let mut adc = self.adc.lock().await;
+            self.sampler
+                .sample(&mut adc, &mut self.ch)
+                .await
+                .map_err(|_| TemperatureSourceFault::Adc)?
+        };
+
+        let temp = self.thermistor.calc_temp(stats.mean);
+        let noise = (self.thermistor.calc_temp(stats.mean + stats.std_dev) - temp).abs();
+
+        Ok(TemperatureReading { temp, noise })
+    }
+}
+
+pub(crate) enum Sensor<'a, SPI> {
+    Thermistor(ThermistorSource<'a>),
+    Thermocouple(Thermocouple<SPI>),
+}
+
+impl<SPI: SpiDevice> TemperatureSource for Sensor<'_, SPI> {
+    async fn read(&mut self) -> Result<TemperatureReading, TemperatureSourceFault> {
+        match self {
+            Sensor::Thermistor(x) => x.read().await,
+            Sensor::Thermocouple(x) => x.read().await,
+        }
+    }
+}
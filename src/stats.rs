@@ -0,0 +1,204 @@
+use bincode::{Decode, Encode};
+use embassy_rp::watchdog::Watchdog;
+
+use crate::interlock::InterlockActionEnum;
+
+const STATS_MAGIC: u8 = 0x5C;
+const STATS_FLUSH_INTERVAL: f32 = 600.0;
+const TRIP_LOG: usize = 3;
+const FAULT_SCRATCH: usize = 0;
+const FAULT_MAGIC: u32 = 0xFA17_0000;
+const FAULT_COUNT_MASK: u32 = 0xFFFF;
+
+/**
+### Interlock trip record
+* `input` is 1-based like on display
+* Board has no clock, `heater_on` is lifetime heater on time at the trip
+*/
+#[derive(Debug, Clone, Copy, Encode, Decode)]
+pub(crate) struct InterlockTrip {
+    pub input: u8,
+    pub action: InterlockActionEnum,
+    pub heater_on: u32,
+}
+
+/**
+### Lifetime usage counters
+* Kept in a separate flash sector, survives settings version changes
+* `trips` holds last interlock trips, newest first
+*/
+#[derive(Debug, Clone, Copy, Encode, Decode)]
+pub(crate) struct StatsData {
+    pub magic: u8,
+    pub heater_on: u32,
+    pub energy: u64,
+    pub runs: u32,
+    pub faults: u32,
+    pub interlock_trips: u32,
+    pub trips: [Option<InterlockTrip>; TRIP_LOG],
+}
+
+impl Default for StatsData {
+    fn default() -> Self {
+        Self {
+            magic: STATS_MAGIC,
+            heater_on: 0,
+            energy: 0,
+            runs: 0,
+            faults: 0,
+            interlock_trips: 0,
+            trips: [None; TRIP_LOG],
+        }
+    }
+}
+
+impl StatsData {
+    pub fn is_valid(&self) -> bool {
+        self.magic == STATS_MAGIC
+    }
+}
+
+/**
+### Usage accumulator
+* `heater_on` in seconds with any demand, `energy` in Ws of duty x `watts`
+* Counters are integers, fractions are kept locally so small steps aren't lost on large totals
+* Flush is due once per `STATS_FLUSH_INTERVAL` of heater on time, after a completed run or an interlock trip
+* Faults are counted by `PendingFaults` and added on next boot
+*/
+pub(crate) struct Stats {
+    data: StatsData,
+    watts: u16,
+    on_time: f32,
+    energy: f32,
+    unflushed: f32,
+    dirty: bool,
+}
+
+impl Stats {
+    pub fn new(data: StatsData, watts: u16) -> Self {
+        Self {
+            data,
+            watts,
+            on_time: 0.0,
+            energy: 0.0,
+            unflushed: 0.0,
+            dirty: false,
+        }
+    }
+
+    pub fn set_watts(&mut self, watts: u16) {
+        self.watts = watts;
+    }
+
+    pub fn data(&self) -> StatsData {
+        self.data
+    }
+
+    pub fn reset(&mut self) {
+        self.data = StatsData::default();
+        self.on_time = 0.0;
+        self.energy = 0.0;
+        self.unflushed = 0.0;
+        self.dirty = true;
+    }
+
+    pub fn update(&mut self, demand: f32, dt: f32) {
+        if demand <= 0.0 {
+            return;
+        }
+
+        //whole seconds are moved to counter, remainder is kept
+        self.on_time += dt;
+        let secs = self.on_time as u32;
+        self.data.heater_on += secs;
+        self.on_time -= secs as f32;
+
+        self.energy += demand * self.watts as f32 * dt;
+        let ws = self.energy as u64;
+        self.data.energy += ws;
+        self.energy -= ws as f32;
+
+        self.unflushed += dt;
+        if self.unflushed >= STATS_FLUSH_INTERVAL {
+            self.dirty = true;
+        }
+    }
+
+    pub fn interlock_trip(&mut self, input: u8, action: InterlockActionEnum) {
+        self.data.interlock_trips += 1;
+        self.data.trips.copy_within(..TRIP_LOG - 1, 1);
+        self.data.trips[0] = Some(InterlockTrip {
+            input,
+            action,
+            heater_on: self.data.heater_on,
+        });
+        self.dirty = true;
+    }
+
+    pub fn run_complete(&mut self) {
+        self.data.runs += 1;
+        self.dirty = true;
+    }
+
+    /**
+    ### Keeps flush due after storage channel was full
+    */
+    pub fn flush_later(&mut self) {
+        self.dirty = true;
+    }
+
+    /**
+    ### Data to write if flush is due
+    * Clears due flag, caller writes result to flash
+    */
+    pub fn take_flush(&mut self) -> Option<StatsData> {
+        if self.dirty {
+            self.dirty = false;
+            self.unflushed = 0.0;
+            Some(self.data)
+        } else {
+            None
+        }
+    }
+}
+
+/**
+### Faults waiting for next boot
+* Panic handler must not erase flash, faults are kept in a watchdog scratch register
+* Scratch survives reset but not power loss, boot adds the count to `StatsData`
+*/
+pub(crate) struct PendingFaults {
+    watchdog: Watchdog,
+}
+
+impl PendingFaults {
+    pub fn new(peripheral: embassy_rp::peripherals::WATCHDOG) -> Self {
+        Self {
+            watchdog: Watchdog::new(peripheral),
+        }
+    }
+
+    pub fn count(&mut self) -> u32 {
+        let raw = self.watchdog.get_scratch(FAULT_SCRATCH);
+        if raw & !FAULT_COUNT_MASK == FAULT_MAGIC {
+            raw & FAULT_COUNT_MASK
+        } else {
+            0
+        }
+    }
+
+    /**
+    ### Counts a fault
+    * For panic_handler
+    * No panic allowed
+    */
+    pub fn record(&mut self) {
+        let count = self.count().saturating_add(1).min(FAULT_COUNT_MASK);
+        self.watchdog
+            .set_scratch(FAULT_SCRATCH, FAULT_MAGIC | count);
+    }
+
+    pub fn clear(&mut self) {
+        self.watchdog.set_scratch(FAULT_SCRATCH, 0);
+    }
+}
@@ -2,20 +2,58 @@ use bincode::{Decode, Encode};
 use embassy_rp::flash;
 
 use crate::{
+    allocator::AllocPolicyEnum,
+    autotune::TuningRuleEnum,
     channels,
+    fan::FanModeEnum,
+    feedforward::{DutyMap, DUTY_MAP_POINTS},
+    interlock::{InterlockSettings, INTERLOCKS},
+    output::OutputModeEnum,
+    plant::PlantModel,
+    schedule::{PidBand, PID_BANDS, PID_BANDS_DEFAULT},
+    source::SensorTypeEnum,
+    stats::StatsData,
+    temperature::{LagCompensationEnum, SyncSourceEnum},
     tools::{SyncStateChannelReceiver, BINCODE_CONFIG},
+    zone::{ZoneSettings, AUX_ZONES, ZONES},
 };
 
 const FLASH_MAGIC: u8 = 0xB5;
-const FLASH_VERSION: u8 = 0x03;
+const FLASH_VERSION: u8 = 0x19;
 const FLASH_SIZE: usize = 2048 * 1024;
 const STORAGE_OFFSET: u32 = (2048 * 1024) - 4096;
 const STORAGE_SIZE: u32 = 4096;
+const STATS_OFFSET: u32 = STORAGE_OFFSET - 4096; //STATS region in memory.x
+const STATS_SIZE: u32 = 4096;
 
 const MAX_WAIT_TIME_DEFAULT: f32 = 10.0;
 const EXTRA_TIME_DEFAULT: f32 = 0.0;
 const TEMP_LEAD_OFFSET_DEFAULT: i16 = 5;
 const TEMP_OFFSET_DEFAULT: i16 = 0;
+const PID_I_MIN_DEFAULT: f32 = 0.0;
+const PID_I_MAX_DEFAULT: f32 = 1.0;
+const BANG_BELOW_DEFAULT: f32 = 1.0;
+const BANG_ABOVE_DEFAULT: f32 = 0.0;
+const BANG_MIN_SWITCH_DEFAULT: f32 = 1.0;
+const CASCADE_P_DEFAULT: f32 = 1.0;
+const CASCADE_I_DEFAULT: f32 = 0.05;
+const CASCADE_MAX_OVER_DEFAULT: u16 = 30;
+const AUTOTUNE_AMPLITUDE_DEFAULT: f32 = 1.0;
+const AUTOTUNE_HYSTERESIS_DEFAULT: f32 = 2.0;
+const AUTOTUNE_CYCLES_DEFAULT: u8 = 6;
+const CHARACTERIZE_DUTY_DEFAULT: f32 = 0.3;
+const IMC_LAMBDA_DEFAULT: f32 = 1.0;
+const OUTPUT_WINDOW_DEFAULT: f32 = 2.0;
+const OUTPUT_MIN_ON_DEFAULT: f32 = 0.1;
+const OUTPUT_MIN_OFF_DEFAULT: f32 = 0.1;
+const MAX_DUTY_DEFAULT: f32 = 1.0;
+const SOFT_START_DEFAULT: f32 = 0.0;
+const CONTROL_PERIOD_DEFAULT: u16 = 100;
+const FAN_COOLING_RATE_DEFAULT: f32 = 1.5;
+const IDLE_TIMEOUT_DEFAULT: u16 = 30;
+const HEATER_WATTS_DEFAULT: u16 = 500;
+const MANUAL_TEMP_MAX_DEFAULT: u16 = 260;
+const MANUAL_TIME_MAX_DEFAULT: u16 = 15;
 
 pub(crate) enum SyncStorageStateEnum {
     WritePid {
@@ -24,12 +62,106 @@ pub(crate) enum SyncStorageStateEnum {
         pid_i: f32,
         pid_d: f32,
     },
+    WritePidLimits {
+        i_min: f32,
+        i_max: f32,
+    },
+    WritePidSchedule {
+        schedule: bool,
+        bands: [PidBand; PID_BANDS],
+    },
+    WriteBangBang {
+        below: f32,
+        above: f32,
+        min_switch: f32,
+    },
     WriteTempSettings {
         wait_time: f32,
         extra_time: f32,
         temp_lead_offset: i16,
         temp_offset: i16,
     },
+    WriteLagCompensation {
+        profile_a: LagCompensationEnum,
+    },
+    WriteSensor {
+        plate_sensor: SensorTypeEnum,
+    },
+    WriteBoardProbe {
+        board_probe: bool,
+        sync_source: SyncSourceEnum,
+    },
+    WriteCascade {
+        cascade: bool,
+        cascade_p: f32,
+        cascade_i: f32,
+        cascade_max_over: u16,
+    },
+    WriteAutoTuneSettings {
+        amplitude: f32,
+        hysteresis: f32,
+        cycles: u8,
+        rule: TuningRuleEnum,
+    },
+    WriteCharacterizeSettings {
+        duty: f32,
+        lambda: f32,
+    },
+    WritePlantModel {
+        model: PlantModel,
+    },
+    WriteFeedForward {
+        enabled: bool,
+        learn: bool,
+        map: DutyMap,
+    },
+    WriteOutput {
+        mode: OutputModeEnum,
+        window: f32,
+        min_on: f32,
+        min_off: f32,
+    },
+    WritePowerLimit {
+        max_duty: f32,
+        max_duty_soak: f32,
+        soft_start: f32,
+    },
+    WriteControlPeriod {
+        period: u16,
+    },
+    WriteFan {
+        mode: FanModeEnum,
+        cooling_rate: f32,
+    },
+    WriteBuzzer {
+        enabled: bool,
+    },
+    WritePost {
+        pulse: bool,
+    },
+    WriteIdleTimeout {
+        timeout: u16,
+    },
+    WriteHeaterWatts {
+        watts: u16,
+    },
+    WriteStats(StatsData),
+    WriteZone {
+        index: usize,
+        settings: ZoneSettings,
+    },
+    WritePowerBudget {
+        budget: u16,
+        policy: AllocPolicyEnum,
+    },
+    WriteInterlock {
+        index: usize,
+        settings: InterlockSettings,
+    },
+    WriteManualLimits {
+        temp_max: u16,
+        time_max: u16,
+    },
 }
 
 #[derive(Debug, Encode, Decode, Clone)]
@@ -40,10 +172,55 @@ pub(crate) struct StorageData {
     pub pid_i: f32,
     pub pid_d: f32,
     pub pid: bool,
+    pub pid_i_min: f32,
+    pub pid_i_max: f32,
+    pub pid_schedule: bool,
+    pub pid_bands: [PidBand; PID_BANDS],
+    pub bang_below: f32,
+    pub bang_above: f32,
+    pub bang_min_switch: f32,
     pub temp_wait_time: f32,
     pub temp_extra_time: f32,
     pub temp_lead_offset: i16,
     pub temp_offset: i16,
+    pub lag_profile_a: LagCompensationEnum,
+    pub plate_sensor: SensorTypeEnum,
+    pub board_probe: bool,
+    pub sync_source: SyncSourceEnum,
+    pub cascade: bool,
+    pub cascade_p: f32,
+    pub cascade_i: f32,
+    pub cascade_max_over: u16,
+    pub autotune_amplitude: f32,
+    pub autotune_hysteresis: f32,
+    pub autotune_cycles: u8,
+    pub autotune_rule: TuningRuleEnum,
+    pub characterize_duty: f32,
+    pub imc_lambda: f32,
+    pub plant_model: PlantModel,
+    pub feedforward: bool,
+    pub feedforward_learn: bool,
+    pub duty_map: DutyMap,
+    pub output_mode: OutputModeEnum,
+    pub output_window: f32,
+    pub output_min_on: f32,
+    pub output_min_off: f32,
+    pub max_duty: f32,
+    pub max_duty_soak: f32,
+    pub soft_start: f32,
+    pub control_period: u16,
+    pub fan_mode: FanModeEnum,
+    pub fan_cooling_rate: f32,
+    pub buzzer: bool,
+    pub post_pulse: bool,
+    pub idle_timeout: u16,
+    pub heater_watts: u16,
+    pub zones: [ZoneSettings; AUX_ZONES],
+    pub power_budget: u16,
+    pub power_policy: AllocPolicyEnum,
+    pub interlocks: [InterlockSettings; INTERLOCKS],
+    pub manual_temp_max: u16,
+    pub manual_time_max: u16,
 }
 
 impl Default for StorageData {
@@ -55,14 +232,74 @@ impl Default for StorageData {
             pid_i: 0.0,
             pid_d: 0.0,
             pid: false,
+            pid_i_min: PID_I_MIN_DEFAULT,
+            pid_i_max: PID_I_MAX_DEFAULT,
+            pid_schedule: false,
+            pid_bands: PID_BANDS_DEFAULT,
+            bang_below: BANG_BELOW_DEFAULT,
+            bang_above: BANG_ABOVE_DEFAULT,
+            bang_min_switch: BANG_MIN_SWITCH_DEFAULT,
             temp_wait_time: MAX_WAIT_TIME_DEFAULT,
             temp_extra_time: EXTRA_TIME_DEFAULT,
             temp_lead_offset: TEMP_LEAD_OFFSET_DEFAULT,
             temp_offset: TEMP_OFFSET_DEFAULT,
+            lag_profile_a: LagCompensationEnum::Fixed,
+            plate_sensor: SensorTypeEnum::Thermistor,
+            board_probe: false,
+            sync_source: SyncSourceEnum::Plate,
+            cascade: false,
+            cascade_p: CASCADE_P_DEFAULT,
+            cascade_i: CASCADE_I_DEFAULT,
+            cascade_max_over: CASCADE_MAX_OVER_DEFAULT,
+            autotune_amplitude: AUTOTUNE_AMPLITUDE_DEFAULT,
+            autotune_hysteresis: AUTOTUNE_HYSTERESIS_DEFAULT,
+            autotune_cycles: AUTOTUNE_CYCLES_DEFAULT,
+            autotune_rule: TuningRuleEnum::ZieglerNichols,
+            characterize_duty: CHARACTERIZE_DUTY_DEFAULT,
+            imc_lambda: IMC_LAMBDA_DEFAULT,
+            plant_model: PlantModel::default(),
+            feedforward: false,
+            feedforward_learn: false,
+            duty_map: [None; DUTY_MAP_POINTS],
+            output_mode: OutputModeEnum::Pwm,
+            output_window: OUTPUT_WINDOW_DEFAULT,
+            output_min_on: OUTPUT_MIN_ON_DEFAULT,
+            output_min_off: OUTPUT_MIN_OFF_DEFAULT,
+            max_duty: MAX_DUTY_DEFAULT,
+            max_duty_soak: MAX_DUTY_DEFAULT,
+            soft_start: SOFT_START_DEFAULT,
+            control_period: CONTROL_PERIOD_DEFAULT,
+            fan_mode: FanModeEnum::Auto,
+            fan_cooling_rate: FAN_COOLING_RATE_DEFAULT,
+            buzzer: true,
+            post_pulse: true,
+            idle_timeout: IDLE_TIMEOUT_DEFAULT,
+            heater_watts: HEATER_WATTS_DEFAULT,
+            zones: [ZoneSettings::default(); AUX_ZONES],
+            power_budget: ZONES as u16 * 100,
+            power_policy: AllocPolicyEnum::Priority,
+            interlocks: [InterlockSettings::default(); INTERLOCKS],
+            manual_temp_max: MANUAL_TEMP_MAX_DEFAULT,
+            manual_time_max: MANUAL_TIME_MAX_DEFAULT,
         }
     }
 }
 
+/**
+### Stored PID gains
+* Leading fields of every storage version, decoded on their own when the version changes
+* Gains keep their meaning across versions, controller output is the same for constant gains
+*/
+#[derive(Decode)]
+struct StorageGains {
+    magic: u8,
+    version: u8,
+    pid_p: f32,
+    pid_i: f32,
+    pid_d: f32,
+    pid: bool,
+}
+
 pub(crate) struct Storage<'a> {
     channel: SyncStateChannelReceiver<'a, SyncStorageStateEnum>,
     storage: StorageData,
@@ -97,6 +334,18 @@ impl<'a> Storage<'a> {
             }
             if storage.version != FLASH_VERSION {
                 storage = StorageData::default();
+
+                //migrate gains from older versions instead of dropping them
+                if let Ok((gains, _)) =
+                    bincode::decode_from_slice::<StorageGains, _>(&buf, BINCODE_CONFIG)
+                {
+                    if gains.magic == FLASH_MAGIC && gains.version < FLASH_VERSION {
+                        storage.pid_p = gains.pid_p;
+                        storage.pid_i = gains.pid_i;
+                        storage.pid_d = gains.pid_d;
+                        storage.pid = gains.pid;
+                    }
+                }
             }
             if storage.pid_p.is_nan() {
                 storage.pid_p = 0.0;
@@ -107,12 +356,88 @@ impl<'a> Storage<'a> {
             if storage.pid_d.is_nan() {
                 storage.pid_d = 0.0;
             }
+            if storage.pid_i_min.is_nan() {
+                storage.pid_i_min = PID_I_MIN_DEFAULT;
+            }
+            if storage.pid_i_max.is_nan() {
+                storage.pid_i_max = PID_I_MAX_DEFAULT;
+            }
+            for band in storage.pid_bands.iter_mut() {
+                if band.p.is_nan() || band.i.is_nan() || band.d.is_nan() {
+                    *band = PidBand::new(band.temp);
+                }
+            }
+            if storage.bang_below.is_nan() {
+                storage.bang_below = BANG_BELOW_DEFAULT;
+            }
+            if storage.bang_above.is_nan() {
+                storage.bang_above = BANG_ABOVE_DEFAULT;
+            }
+            if storage.bang_min_switch.is_nan() {
+                storage.bang_min_switch = BANG_MIN_SWITCH_DEFAULT;
+            }
             if storage.temp_wait_time.is_nan() {
                 storage.temp_wait_time = MAX_WAIT_TIME_DEFAULT;
             }
             if storage.temp_extra_time.is_nan() {
                 storage.temp_extra_time = EXTRA_TIME_DEFAULT;
             }
+            if storage.cascade_p.is_nan() {
+                storage.cascade_p = CASCADE_P_DEFAULT;
+            }
+            if storage.cascade_i.is_nan() {
+                storage.cascade_i = CASCADE_I_DEFAULT;
+            }
+            if storage.autotune_amplitude.is_nan() {
+                storage.autotune_amplitude = AUTOTUNE_AMPLITUDE_DEFAULT;
+            }
+            if storage.autotune_hysteresis.is_nan() {
+                storage.autotune_hysteresis = AUTOTUNE_HYSTERESIS_DEFAULT;
+            }
+            if storage.characterize_duty.is_nan() {
+                storage.characterize_duty = CHARACTERIZE_DUTY_DEFAULT;
+            }
+            if storage.imc_lambda.is_nan() {
+                storage.imc_lambda = IMC_LAMBDA_DEFAULT;
+            }
+            if storage.plant_model.gain.is_nan()
+                || storage.plant_model.tau.is_nan()
+                || storage.plant_model.dead_time.is_nan()
+                || storage.plant_model.ambient.is_nan()
+            {
+                storage.plant_model = PlantModel::default();
+            }
+            for duty in storage.duty_map.iter_mut() {
+                if duty.is_some_and(|x| x.is_nan()) {
+                    *duty = None;
+                }
+            }
+            if storage.output_window.is_nan() {
+                storage.output_window = OUTPUT_WINDOW_DEFAULT;
+            }
+            if storage.output_min_on.is_nan() {
+                storage.output_min_on = OUTPUT_MIN_ON_DEFAULT;
+            }
+            if storage.output_min_off.is_nan() {
+                storage.output_min_off = OUTPUT_MIN_OFF_DEFAULT;
+            }
+            if storage.max_duty.is_nan() {
+                storage.max_duty = MAX_DUTY_DEFAULT;
+            }
+            if storage.max_duty_soak.is_nan() {
+                storage.max_duty_soak = MAX_DUTY_DEFAULT;
+            }
+            if storage.soft_start.is_nan() {
+                storage.soft_start = SOFT_START_DEFAULT;
+            }
+            if storage.fan_cooling_rate.is_nan() {
+                storage.fan_cooling_rate = FAN_COOLING_RATE_DEFAULT;
+            }
+            for zone in storage.zones.iter_mut() {
+                if zone.pid_p.is_nan() || zone.pid_i.is_nan() || zone.pid_d.is_nan() {
+                    *zone = ZoneSettings::default();
+                }
+            }
 
             storage
         } else {
@@ -120,6 +445,45 @@ impl<'a> Storage<'a> {
         }
     }
 
+    /**
+    ### Reads usage statistics sector
+    * Invalid or missing data starts from zero
+    */
+    pub fn stats_read(
+        flash: &mut flash::Flash<'a, embassy_rp::peripherals::FLASH, flash::Blocking, FLASH_SIZE>,
+    ) -> StatsData {
+        let mut buf = [0; STATS_SIZE as usize];
+        if flash.blocking_read(STATS_OFFSET, &mut buf).is_ok() {
+            let stats: StatsData = bincode::decode_from_slice(&buf, BINCODE_CONFIG)
+                .unwrap_or_default()
+                .0;
+            if stats.is_valid() {
+                return stats;
+            }
+        }
+        StatsData::default()
+    }
+
+    /**
+    ### Writes usage statistics sector
+    * Settings sector is not rewritten
+    */
+    pub fn stats_write(
+        flash: &mut flash::Flash<'a, embassy_rp::peripherals::FLASH, flash::Blocking, FLASH_SIZE>,
+        stats: StatsData,
+    ) {
+        let mut buf = [0; STATS_SIZE as usize];
+
+        bincode::encode_into_slice(stats, &mut buf, BINCODE_CONFIG).expect("stats enc fail");
+
+        flash
+            .blocking_erase(STATS_OFFSET, STATS_OFFSET + STATS_SIZE)
+            .expect("stats erase fail");
+        flash
+            .blocking_write(STATS_OFFSET, &buf)
+            .expect("stats write fail");
+    }
+
     pub async fn flash_task(&mut self) -> ! {
         let rx = self.channel;
         loop {
@@ -136,6 +500,23 @@ impl<'a> Storage<'a> {
                     self.storage.pid_d = pid_d;
                     self.storage.pid = pid;
                 }
+                SyncStorageStateEnum::WritePidLimits { i_min, i_max } => {
+                    self.storage.pid_i_min = i_min;
+                    self.storage.pid_i_max = i_max;
+                }
+                SyncStorageStateEnum::WritePidSchedule { schedule, bands } => {
+                    self.storage.pid_schedule = schedule;
+                    self.storage.pid_bands = bands;
+                }
+                SyncStorageStateEnum::WriteBangBang {
+                    below,
+                    above,
+                    min_switch,
+                } => {
+                    self.storage.bang_below = below;
+                    self.storage.bang_above = above;
+                    self.storage.bang_min_switch = min_switch;
+                }
                 SyncStorageStateEnum::WriteTempSettings {
                     wait_time,
                     extra_time,
@@ -147,6 +528,118 @@ impl<'a> Storage<'a> {
                     self.storage.temp_lead_offset = temp_lead_offset;
                     self.storage.temp_offset = temp_offset;
                 }
+                SyncStorageStateEnum::WriteLagCompensation { profile_a } => {
+                    self.storage.lag_profile_a = profile_a;
+                }
+                SyncStorageStateEnum::WriteSensor { plate_sensor } => {
+                    self.storage.plate_sensor = plate_sensor;
+                }
+                SyncStorageStateEnum::WriteBoardProbe {
+                    board_probe,
+                    sync_source,
+                } => {
+                    self.storage.board_probe = board_probe;
+                    self.storage.sync_source = sync_source;
+                }
+                SyncStorageStateEnum::WriteCascade {
+                    cascade,
+                    cascade_p,
+                    cascade_i,
+                    cascade_max_over,
+                } => {
+                    self.storage.cascade = cascade;
+                    self.storage.cascade_p = cascade_p;
+                    self.storage.cascade_i = cascade_i;
+                    self.storage.cascade_max_over = cascade_max_over;
+                }
+                SyncStorageStateEnum::WriteAutoTuneSettings {
+                    amplitude,
+                    hysteresis,
+                    cycles,
+                    rule,
+                } => {
+                    self.storage.autotune_amplitude = amplitude;
+                    self.storage.autotune_hysteresis = hysteresis;
+                    self.storage.autotune_cycles = cycles;
+                    self.storage.autotune_rule = rule;
+                }
+                SyncStorageStateEnum::WriteCharacterizeSettings { duty, lambda } => {
+                    self.storage.characterize_duty = duty;
+                    self.storage.imc_lambda = lambda;
+                }
+                SyncStorageStateEnum::WritePlantModel { model } => {
+                    self.storage.plant_model = model;
+                }
+                SyncStorageStateEnum::WriteFeedForward {
+                    enabled,
+                    learn,
+                    map,
+                } => {
+                    self.storage.feedforward = enabled;
+                    self.storage.feedforward_learn = learn;
+                    self.storage.duty_map = map;
+                }
+                SyncStorageStateEnum::WriteOutput {
+                    mode,
+                    window,
+                    min_on,
+                    min_off,
+                } => {
+                    self.storage.output_mode = mode;
+                    self.storage.output_window = window;
+                    self.storage.output_min_on = min_on;
+                    self.storage.output_min_off = min_off;
+                }
+                SyncStorageStateEnum::WritePowerLimit {
+                    max_duty,
+                    max_duty_soak,
+                    soft_start,
+                } => {
+                    self.storage.max_duty = max_duty;
+                    self.storage.max_duty_soak = max_duty_soak;
+                    self.storage.soft_start = soft_start;
+                }
+                SyncStorageStateEnum::WriteControlPeriod { period } => {
+                    self.storage.control_period = period;
+                }
+                SyncStorageStateEnum::WriteFan { mode, cooling_rate } => {
+                    self.storage.fan_mode = mode;
+                    self.storage.fan_cooling_rate = cooling_rate;
+                }
+                SyncStorageStateEnum::WriteBuzzer { enabled } => {
+                    self.storage.buzzer = enabled;
+                }
+                SyncStorageStateEnum::WritePost { pulse } => {
+                    self.storage.post_pulse = pulse;
+                }
+                SyncStorageStateEnum::WriteIdleTimeout { timeout } => {
+                    self.storage.idle_timeout = timeout;
+                }
+                SyncStorageStateEnum::WriteHeaterWatts { watts } => {
+                    self.storage.heater_watts = watts;
+                }
+                SyncStorageStateEnum::WriteZone { index, settings } => {
+                    if let Some(zone) = self.storage.zones.get_mut(index) {
+                        *zone = settings;
+                    }
+                }
+                SyncStorageStateEnum::WritePowerBudget { budget, policy } => {
+                    self.storage.power_budget = budget;
+                    self.storage.power_policy = policy;
+                }
+                SyncStorageStateEnum::WriteInterlock { index, settings } => {
+                    if let Some(interlock) = self.storage.interlocks.get_mut(index) {
+                        *interlock = settings;
+                    }
+                }
+                SyncStorageStateEnum::WriteManualLimits { temp_max, time_max } => {
+                    self.storage.manual_temp_max = temp_max;
+                    self.storage.manual_time_max = time_max;
+                }
+                SyncStorageStateEnum::WriteStats(stats) => {
+                    Self::stats_write(&mut self.flash, stats);
+                    continue;
+                }
             }
 
             let mut buf = [0; STORAGE_SIZE as usize];
@@ -1,18 +1,26 @@
-use core::{f32::consts::PI, fmt::Debug, time::Duration};
+use core::{fmt::Debug, time::Duration};
 
-use micromath::F32Ext;
+use bincode::{Decode, Encode};
 
-use crate::{menu::SyncMenuStateEnum, tools::SyncStateChannelSender};
+use reflow_plate_logic::profile::{lag_lead, lead_ramp};
+
+use crate::{
+    autotune::RelayAutoTune,
+    buzzer::SyncBuzzerStateEnum,
+    menu::SyncMenuStateEnum,
+    plant::{PlantModel, StepResponse},
+};
 
 const RUNAWAY_TARGET_TEMP_THRESHOLD: u16 = 5;
 const RUNAWAY_TEMP_THRESHOLD: u16 = 2;
 const RUNAWAY_INTERVAL: u16 = 5;
 const RUNAWAY_ERROR_MAX: f32 = 120.0;
 const RUNAWAY_CURR_ERROR_MAX: u16 = 50;
-
-const TUNE_PID_DELTA: u16 = 5;
-const PID_FIRST_SAMPLE: u8 = 3;
-const PID_LAST_SAMPLE: u8 = 6;
+const LAG_LEAD_MAX: f32 = 50.0;
+const ALERT_REACHED_BAND: u16 = 2;
+const ALERT_COMPLETE_TEMP: u16 = 50;
+const MANUAL_AMBIENT: f32 = 25.0;
+const MANUAL_RUNAWAY_GAIN: f32 = 1.0; //°C per % duty, below any working plate
 
 #[derive(Clone)]
 pub struct Hidden<T>(T);
@@ -26,12 +34,31 @@ impl<T> Debug for Hidden<T> {
 #[derive(Debug, Clone)]
 pub enum TemperatureProfileEnum {
     Static,
+    Bake,
     ProfileA {
         state: TemperatureProfileAState,
     },
     AutoCalibrate {
         state: TemperatureAutoCalibrateState,
     },
+    Characterize {
+        state: TemperatureCharacterizeState,
+    },
+    Manual {
+        duty: u8,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Encode, Decode)]
+pub enum SyncSourceEnum {
+    Plate,
+    Board,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Encode, Decode)]
+pub enum LagCompensationEnum {
+    Fixed,
+    Model,
 }
 
 #[derive(Debug, Clone)]
@@ -59,8 +86,8 @@ impl Default for TemperatureProfileAState {
 #[derive(Debug, Clone, PartialEq)]
 pub enum TemperatureAutoCalibrateState {
     FirstRamp,
-    PeakHeating { n: u8 },
-    PeakCooling { n: u8 },
+    RelayHeating { n: u8 },
+    RelayCooling { n: u8 },
     Cooldown,
 }
 
@@ -70,42 +97,67 @@ impl Default for TemperatureAutoCalibrateState {
     }
 }
 
-pub struct TemperatureProfile<'a> {
+#[derive(Debug, Clone, PartialEq)]
+pub enum TemperatureCharacterizeState {
+    Step,
+    Cooldown,
+}
+
+impl Default for TemperatureCharacterizeState {
+    fn default() -> Self {
+        Self::Step
+    }
+}
+
+pub struct TemperatureProfile {
     peak: u16,
     profile: TemperatureProfileEnum,
     time: f32,
     state_start: f32,
     temperature: u16,
-    heating: bool,
+    precise_temperature: f32,
+    board_temperature: Option<u16>,
+    sync_source: SyncSourceEnum,
+    cascade: bool,
     temp_wait_time: f32,
     temp_extra_time: f32,
     temp_lead_offset: i16,
     temp_offset: i16,
+    lag_profile_a: LagCompensationEnum,
+    plant_model: PlantModel,
+    imc_lambda: f32,
+    manual_temp_max: u16,
+    manual_time_max: f32,
     curr_max_temp: u16,
     last_target: u16,
     last_max: u16,
     last_period: f32,
     runaway_error: f32,
-    temp_drop_peak: u16,
-    peaks: [(f32, f32); 3],
-    menu_tx: SyncStateChannelSender<'a, SyncMenuStateEnum>,
+    autotune: RelayAutoTune,
+    step_response: StepResponse,
+    last_alert: Option<SyncBuzzerStateEnum>,
+    menu_msg: Option<SyncMenuStateEnum>,
 }
 
 impl TemperatureProfileEnum {
     fn reset(&mut self) {
         match self {
-            TemperatureProfileEnum::Static => {}
+            TemperatureProfileEnum::Static | TemperatureProfileEnum::Bake => {}
             TemperatureProfileEnum::ProfileA { state } => {
                 *state = TemperatureProfileAState::FirstRamp;
             }
             TemperatureProfileEnum::AutoCalibrate { state } => {
                 *state = TemperatureAutoCalibrateState::FirstRamp;
             }
+            TemperatureProfileEnum::Characterize { state } => {
+                *state = TemperatureCharacterizeState::Step;
+            }
+            TemperatureProfileEnum::Manual { .. } => {}
         }
     }
 }
 
-impl<'a> TemperatureProfile<'a> {
+impl TemperatureProfile {
     pub fn new(
         peak: u16,
         profile: TemperatureProfileEnum,
@@ -113,7 +165,6 @@ impl<'a> TemperatureProfile<'a> {
         temp_extra_time: f32,
         temp_lead_offset: i16,
         temp_offset: i16,
-        menu_tx: SyncStateChannelSender<'a, SyncMenuStateEnum>,
     ) -> Self {
         Self {
             peak,
@@ -121,19 +172,28 @@ impl<'a> TemperatureProfile<'a> {
             time: 0.0,
             state_start: 0.0,
             temperature: 0,
-            heating: false,
+            precise_temperature: 0.0,
+            board_temperature: None,
+            sync_source: SyncSourceEnum::Plate,
+            cascade: false,
             temp_wait_time,
             temp_extra_time,
             temp_lead_offset,
             temp_offset,
+            lag_profile_a: LagCompensationEnum::Fixed,
+            plant_model: PlantModel::default(),
+            imc_lambda: 1.0,
+            manual_temp_max: 0,
+            manual_time_max: 0.0,
             curr_max_temp: 0,
             last_target: 0,
             last_max: 0,
             last_period: 0.0,
             runaway_error: 0.0,
-            temp_drop_peak: 0,
-            peaks: [(0.0, 0.0), (0.0, 0.0), (0.0, 0.0)],
-            menu_tx,
+            autotune: RelayAutoTune::new(1.0, 1.0, 1),
+            step_response: StepResponse::new(0.0),
+            last_alert: None,
+            menu_msg: None,
         }
     }
 
@@ -150,10 +210,49 @@ impl<'a> TemperatureProfile<'a> {
         self.temp_offset = temp_offset;
     }
 
+    pub fn set_autotune_settings(&mut self, amplitude: f32, hysteresis: f32, cycles: u8) {
+        self.autotune.set_settings(amplitude, hysteresis, cycles);
+    }
+
+    pub fn set_characterize_settings(&mut self, duty: f32) {
+        self.step_response.set_duty(duty);
+    }
+
+    pub fn set_lag_compensation(&mut self, profile_a: LagCompensationEnum) {
+        self.lag_profile_a = profile_a;
+    }
+
+    pub fn set_plant_model(&mut self, model: PlantModel, imc_lambda: f32) {
+        self.plant_model = model;
+        self.imc_lambda = imc_lambda;
+    }
+
+    /**
+    ### Manual power mode limits
+    * `temp_max` in °C, plate over-temp panics
+    * `time_max` in minutes, output is switched off after it
+    */
+    pub fn set_manual_limits(&mut self, temp_max: u16, time_max: u16) {
+        self.manual_temp_max = temp_max;
+        self.manual_time_max = time_max as f32 * 60.0;
+    }
+
+    pub fn set_sync_source(&mut self, sync_source: SyncSourceEnum) {
+        self.sync_source = sync_source;
+    }
+
+    pub fn set_cascade(&mut self, cascade: bool) {
+        self.cascade = cascade;
+    }
+
     pub fn set_profile(&mut self, profile: TemperatureProfileEnum) {
         self.profile = profile;
     }
 
+    pub fn peak(&self) -> u16 {
+        self.peak
+    }
+
     pub fn set_peak(&mut self, peak: u16) {
         self.peak = peak;
     }
@@ -168,13 +267,20 @@ impl<'a> TemperatureProfile<'a> {
         self.runaway_error = 0.0;
         self.last_max = 0;
         self.curr_max_temp = 0;
+        self.autotune.reset();
+        self.step_response.reset();
+        self.last_alert = None;
     }
 
-    pub async fn get_current_target(&mut self) -> u16 {
+    pub fn get_current_target(&mut self) -> u16 {
         self.last_target = match &self.profile {
-            TemperatureProfileEnum::Static => self.get_current_target_static(),
+            TemperatureProfileEnum::Static | TemperatureProfileEnum::Bake => {
+                self.get_current_target_static()
+            }
             TemperatureProfileEnum::ProfileA { .. } => self.get_current_target_prof_a(),
-            TemperatureProfileEnum::AutoCalibrate { .. } => self.get_current_autocalibrate().await,
+            TemperatureProfileEnum::AutoCalibrate { .. } => self.get_current_autocalibrate(),
+            TemperatureProfileEnum::Characterize { .. } => self.get_current_characterize(),
+            TemperatureProfileEnum::Manual { duty } => self.get_current_target_manual(*duty),
         };
 
         self.last_target
@@ -184,7 +290,31 @@ impl<'a> TemperatureProfile<'a> {
         self.peak
     }
 
+    /**
+    ### Expected plate temperature at manual duty
+    * Used by runaway check and display, output is set by `get_output_override`
+    * Characterized plate: steady state of model, otherwise a conservative rise per % duty
+    * 0 after `manual_time_max`
+    */
+    fn get_current_target_manual(&self, duty: u8) -> u16 {
+        if self.time >= self.manual_time_max {
+            return 0;
+        }
+
+        let expected = if self.plant_model.valid() {
+            self.plant_model.ambient + 0.8 * self.plant_model.gain * duty as f32
+        } else {
+            MANUAL_AMBIENT + MANUAL_RUNAWAY_GAIN * duty as f32
+        };
+        (expected as u16).min(self.manual_temp_max)
+    }
+
     fn get_current_target_prof_a(&mut self) -> u16 {
+        //evaluated before profile state is borrowed
+        let sync_first = self.sync_reached(150);
+        let sync_second = self.sync_reached(220);
+        let sync_peak = self.sync_reached(self.peak);
+
         match &mut self.profile {
             TemperatureProfileEnum::ProfileA { state } => match state {
                 TemperatureProfileAState::FirstRamp => {
@@ -193,19 +323,17 @@ impl<'a> TemperatureProfile<'a> {
                         self.state_start = self.time;
                     }
                     ((self.time * 4.0) as u16)
-                        .saturating_add_signed(self.temp_lead_offset)
-                        .saturating_add_signed(self.temp_offset) //time: 0..38 => temp: 0..152
+                        .saturating_add_signed(self.lead_offset())
+                        .saturating_add_signed(self.offset()) //time: 0..38 => temp: 0..152
                 }
                 TemperatureProfileAState::FirstRampSync => {
-                    if self.time >= self.state_start + self.temp_wait_time
-                        || self.temperature >= 150u16.saturating_add_signed(self.temp_offset)
-                    {
+                    if self.time >= self.state_start + self.temp_wait_time || sync_first {
                         *state = TemperatureProfileAState::FirstRampExtra;
                         self.state_start = self.time;
                     }
                     150u16
-                        .saturating_add_signed(self.temp_lead_offset)
-                        .saturating_add_signed(self.temp_offset)
+                        .saturating_add_signed(self.lead_offset())
+                        .saturating_add_signed(self.offset())
                 }
                 TemperatureProfileAState::FirstRampExtra => {
                     if self.time >= self.state_start + self.temp_extra_time {
@@ -213,8 +341,8 @@ impl<'a> TemperatureProfile<'a> {
                         self.state_start = self.time;
                     }
                     150u16
-                        .saturating_add_signed(self.temp_lead_offset)
-                        .saturating_add_signed(self.temp_offset)
+                        .saturating_add_signed(self.lead_offset())
+                        .saturating_add_signed(self.offset())
                 }
                 TemperatureProfileAState::PreHeat => {
                     if self.time >= self.state_start + 80.0 {
@@ -223,8 +351,8 @@ impl<'a> TemperatureProfile<'a> {
                     }
                     let diff = self.time - self.state_start;
                     (150 + (diff * 30.0 / 80.0) as u16)
-                        .saturating_add_signed(self.temp_lead_offset)
-                        .saturating_add_signed(self.temp_offset) //time: 38..120 => temp: 150..180
+                        .saturating_add_signed(self.lead_offset())
+                        .saturating_add_signed(self.offset()) //time: 38..120 => temp: 150..180
                 }
                 TemperatureProfileAState::PreHeatExtra => {
                     if self.time >= self.state_start + self.temp_extra_time {
@@ -232,8 +360,8 @@ impl<'a> TemperatureProfile<'a> {
                         self.state_start = self.time;
                     }
                     180u16
-                        .saturating_add_signed(self.temp_lead_offset)
-                        .saturating_add_signed(self.temp_offset)
+                        .saturating_add_signed(self.lead_offset())
+                        .saturating_add_signed(self.offset())
                 }
                 TemperatureProfileAState::SecondRamp => {
                     if self.time >= self.state_start + 13.0 {
@@ -242,24 +370,22 @@ impl<'a> TemperatureProfile<'a> {
                     }
                     let diff = self.time - self.state_start;
                     (180 + (diff * 40.0 / 13.0) as u16)
-                        .saturating_add_signed(self.temp_lead_offset)
-                        .saturating_add_signed(self.temp_offset) //time: 120..133 => temp: 180..220
+                        .saturating_add_signed(self.lead_offset())
+                        .saturating_add_signed(self.offset()) //time: 120..133 => temp: 180..220
                 }
                 TemperatureProfileAState::SecondRampSync => {
-                    if self.time >= self.state_start + self.temp_wait_time
-                        || self.temperature >= 220u16.saturating_add_signed(self.temp_offset)
-                    {
+                    if self.time >= self.state_start + self.temp_wait_time || sync_second {
                         *state = TemperatureProfileAState::SecondRampExtra;
                         self.state_start = self.time;
                     }
-                    220u16.saturating_add_signed(self.temp_lead_offset)
+                    220u16.saturating_add_signed(self.lead_offset())
                 }
                 TemperatureProfileAState::SecondRampExtra => {
                     if self.time >= self.state_start + self.temp_extra_time {
                         *state = TemperatureProfileAState::PeakRamp;
                         self.state_start = self.time;
                     }
-                    220u16.saturating_add_signed(self.temp_lead_offset)
+                    220u16.saturating_add_signed(self.lead_offset())
                 }
                 TemperatureProfileAState::PeakRamp => {
                     if self.time >= self.state_start + 20.0 {
@@ -267,30 +393,23 @@ impl<'a> TemperatureProfile<'a> {
                         self.state_start = self.time;
                     }
                     let diff = self.time - self.state_start;
-                    let temp_diff = self.peak
-                        - 220u16
-                            .saturating_add_signed(self.temp_lead_offset)
-                            .saturating_add_signed(self.temp_offset);
-                    (220 + (temp_diff as f32 * diff / 20.0) as u16)
-                        .saturating_add_signed(self.temp_lead_offset)
-                        .saturating_add_signed(self.temp_offset)
+                    lead_ramp(220, self.peak, self.lead_offset(), diff / 20.0)
+                        .saturating_add_signed(self.offset())
                     //time: 133..153 => temp: 220..peak
                 }
                 TemperatureProfileAState::PeakRampSync => {
-                    if self.time >= self.state_start + self.temp_wait_time
-                        || self.temperature >= self.peak.saturating_add_signed(self.temp_offset)
-                    {
+                    if self.time >= self.state_start + self.temp_wait_time || sync_peak {
                         *state = TemperatureProfileAState::PeakRampExtra;
                         self.state_start = self.time;
                     }
-                    self.peak.saturating_add_signed(self.temp_offset)
+                    self.peak.saturating_add_signed(self.offset())
                 }
                 TemperatureProfileAState::PeakRampExtra => {
                     if self.time >= self.state_start + self.temp_extra_time {
                         *state = TemperatureProfileAState::Cooldown;
                         self.state_start = self.time;
                     }
-                    self.peak.saturating_add_signed(self.temp_offset)
+                    self.peak.saturating_add_signed(self.offset())
                 }
                 TemperatureProfileAState::Cooldown => 0,
             },
@@ -298,110 +417,332 @@ impl<'a> TemperatureProfile<'a> {
         }
     }
 
-    async fn get_current_autocalibrate(&mut self) -> u16 {
+    /**
+    ### Plate lag compensation
+    * Not applied in cascade mode, outer loop tracks board temperature directly
+    * Fixed: `temp_lead_offset` is added to targets
+    * Model: setpoint is advanced by predicted lag, fixed offset is used until plate is characterized
+    */
+    fn lead_offset(&self) -> i16 {
+        if self.cascade {
+            return 0;
+        }
+
+        let lag = match self.profile {
+            TemperatureProfileEnum::ProfileA { .. } => self.lag_profile_a,
+            _ => LagCompensationEnum::Fixed,
+        };
+        match (lag, self.plant_model.valid()) {
+            (LagCompensationEnum::Model, true) => self.model_lead(),
+            _ => self.temp_lead_offset,
+        }
+    }
+
+    /**
+    ### Predicted plate lag behind ramping target
+    * Closed loop tuned by IMC behaves like `e^(-L*s) / (lambda*s + 1)`
+    * On a ramp it lags by `rate * (lambda + L)`, target is advanced by that much
+    */
+    fn model_lead(&self) -> i16 {
+        let dead_time = self.plant_model.dead_time;
+        let lambda = (self.imc_lambda * self.plant_model.tau).max(dead_time);
+        lag_lead(self.ramp_rate(), lambda, dead_time, LAG_LEAD_MAX)
+    }
+
+    /**
+    ### Nominal target slope of current stage in °C/s
+    */
+    fn ramp_rate(&self) -> f32 {
+        match &self.profile {
+            TemperatureProfileEnum::ProfileA { state } => match state {
+                TemperatureProfileAState::FirstRamp => 4.0,
+                TemperatureProfileAState::PreHeat => 30.0 / 80.0,
+                TemperatureProfileAState::SecondRamp => 40.0 / 13.0,
+                TemperatureProfileAState::PeakRamp => self.peak.saturating_sub(220) as f32 / 20.0,
+                _ => 0.0,
+            },
+            _ => 0.0,
+        }
+    }
+
+    fn offset(&self) -> i16 {
+        if self.cascade {
+            0
+        } else {
+            self.temp_offset
+        }
+    }
+
+    /**
+    ### Checks if sync stage target is reached
+    * Board probe reads the joint temperature, plate offset is not applied
+    * Falls back to plate sensor when board probe is disabled
+    */
+    fn sync_reached(&self, temp: u16) -> bool {
+        match (self.sync_source, self.board_temperature) {
+            (SyncSourceEnum::Board, Some(board_temp)) => board_temp >= temp,
+            _ => self.temperature >= temp.saturating_add_signed(self.temp_offset),
+        }
+    }
+
+    fn get_current_autocalibrate(&mut self) -> u16 {
         match &mut self.profile {
             TemperatureProfileEnum::AutoCalibrate { state } => {
-                let menu_tx = self.menu_tx;
+                let temp = self.precise_temperature;
+                let upper = self.peak as f32 + self.autotune.hysteresis();
+                let lower = self.peak as f32 - self.autotune.hysteresis();
                 match *state {
                     TemperatureAutoCalibrateState::FirstRamp => {
-                        if self.temperature >= self.peak {
-                            self.temp_drop_peak = u16::MAX;
-                            *state = TemperatureAutoCalibrateState::PeakCooling { n: 0 };
+                        if temp >= upper {
+                            *state = TemperatureAutoCalibrateState::RelayCooling { n: 0 };
+                        }
+                    }
+                    TemperatureAutoCalibrateState::RelayHeating { n } => {
+                        self.autotune.track(temp);
+                        if temp >= upper {
+                            *state = TemperatureAutoCalibrateState::RelayCooling { n };
                         }
-                        self.peak
                     }
-                    TemperatureAutoCalibrateState::PeakHeating { n } => {
-                        if !self.heating && self.temperature >= self.peak {
-                            self.peaks[0] = self.peaks[1];
-                            self.peaks[1] = self.peaks[2];
-                            self.peaks[2].0 = self.temp_drop_peak.into();
-                            self.peaks[2].1 = self.time;
-                            self.temp_drop_peak = 0;
-                            let next_state = if n >= PID_LAST_SAMPLE {
+                    TemperatureAutoCalibrateState::RelayCooling { n } => {
+                        self.autotune.track(temp);
+                        if temp <= lower {
+                            let recorded = self.autotune.cycle(self.time);
+                            let done = self.autotune.done();
+                            *state = if done {
                                 TemperatureAutoCalibrateState::Cooldown
                             } else {
-                                TemperatureAutoCalibrateState::PeakCooling { n }
+                                TemperatureAutoCalibrateState::RelayHeating { n: n + 1 }
                             };
 
-                            *state = next_state.clone();
-
-                            if n >= PID_FIRST_SAMPLE {
-                                let pid = self.calc_pid();
-                                menu_tx
-                                    .send(SyncMenuStateEnum::PidAutoTune {
-                                        iteration: n,
-                                        pid_p: pid.0,
-                                        pid_i: pid.1,
-                                        pid_d: pid.2,
-                                        done: next_state == TemperatureAutoCalibrateState::Cooldown,
-                                    })
-                                    .await;
-                            }
-                        } else if self.temperature < self.temp_drop_peak {
-                            self.temp_drop_peak = self.temperature;
-                        }
-                        self.peak
-                    }
-                    TemperatureAutoCalibrateState::PeakCooling { n } => {
-                        if self.heating && self.temperature <= self.peak - TUNE_PID_DELTA {
-                            self.peaks[0] = self.peaks[1];
-                            self.peaks[1] = self.peaks[2];
-                            self.peaks[2].0 = self.temp_drop_peak.into();
-                            self.peaks[2].1 = self.time;
-                            self.temp_drop_peak = u16::MAX;
-                            *state = TemperatureAutoCalibrateState::PeakHeating { n: n + 1 };
-                            if n >= PID_FIRST_SAMPLE {
-                                let pid = self.calc_pid();
-                                menu_tx
-                                    .send(SyncMenuStateEnum::PidAutoTune {
-                                        iteration: n,
-                                        pid_p: pid.0,
-                                        pid_i: pid.1,
-                                        pid_d: pid.2,
-                                        done: false,
-                                    })
-                                    .await;
+                            if recorded {
+                                if let Some(result) = self.autotune.result() {
+                                    self.menu_msg = Some(SyncMenuStateEnum::PidAutoTune {
+                                        iteration: self.autotune.count() as u8,
+                                        result,
+                                        done,
+                                    });
+                                }
                             }
-                        } else if self.temperature > self.temp_drop_peak {
-                            self.temp_drop_peak = self.temperature;
                         }
-                        self.peak - TUNE_PID_DELTA
                     }
-                    TemperatureAutoCalibrateState::Cooldown => 0,
+                    TemperatureAutoCalibrateState::Cooldown => return 0,
                 }
+                self.peak
             }
             _ => panic!("wrong profile, expected Autocalibrate"),
         }
     }
 
-    fn calc_pid(&self) -> (f32, f32, f32) {
-        let temp_diff = self.peaks[2].0 - self.peaks[1].0;
-        let time_diff = self.peaks[2].1 - self.peaks[0].1;
+    /**
+    ### Open-loop step from ambient
+    * Peak temperature is the safety limit, step ends when it is reached
+    * Model is fitted from the recorded response and sent to menu
+    */
+    fn get_current_characterize(&mut self) -> u16 {
+        match &mut self.profile {
+            TemperatureProfileEnum::Characterize { state } => match state {
+                TemperatureCharacterizeState::Step => {
+                    let last_elapsed = self.step_response.elapsed() as u16;
+                    let finished = self.step_response.record(
+                        self.time,
+                        self.precise_temperature,
+                        self.peak as f32,
+                    );
+                    let elapsed = self.step_response.elapsed() as u16;
+                    if finished {
+                        *state = TemperatureCharacterizeState::Cooldown;
+                        self.menu_msg = Some(SyncMenuStateEnum::Characterize {
+                            elapsed,
+                            model: self.step_response.fit(),
+                            done: true,
+                        });
+                    } else if elapsed != last_elapsed {
+                        self.menu_msg = Some(SyncMenuStateEnum::Characterize {
+                            elapsed,
+                            model: None,
+                            done: false,
+                        });
+                    }
+                    self.peak
+                }
+                TemperatureCharacterizeState::Cooldown => 0,
+            },
+            _ => panic!("wrong profile, expected Characterize"),
+        }
+    }
 
-        let amplitude = temp_diff.abs() * 0.5f32;
+    /**
+    ### Operator alert for current stage
+    * Returned once when stage changes
+    * Static, Bake: plate reached target, board can be placed
+    * ProfileA: peak reached, cooldown (remove board), cooled down (complete)
+    */
+    pub fn take_alert(&mut self) -> Option<SyncBuzzerStateEnum> {
+        let alert = match &self.profile {
+            TemperatureProfileEnum::Static | TemperatureProfileEnum::Bake => {
+                let reached = self.peak > 0 && self.temperature + ALERT_REACHED_BAND >= self.peak;
+                //latched until next run
+                if reached || self.last_alert.is_some() {
+                    Some(SyncBuzzerStateEnum::PlaceBoard)
+                } else {
+                    None
+                }
+            }
+            TemperatureProfileEnum::ProfileA { state } => match state {
+                TemperatureProfileAState::PeakRampExtra => Some(SyncBuzzerStateEnum::PeakReached),
+                TemperatureProfileAState::Cooldown if self.temperature < ALERT_COMPLETE_TEMP => {
+                    Some(SyncBuzzerStateEnum::Complete)
+                }
+                TemperatureProfileAState::Cooldown => Some(SyncBuzzerStateEnum::RemoveBoard),
+                _ => None,
+            },
+            TemperatureProfileEnum::AutoCalibrate {
+                state: TemperatureAutoCalibrateState::Cooldown,
+            }
+            | TemperatureProfileEnum::Characterize {
+                state: TemperatureCharacterizeState::Cooldown,
+            } => Some(SyncBuzzerStateEnum::Complete),
+            TemperatureProfileEnum::Manual { .. } if self.time >= self.manual_time_max => {
+                Some(SyncBuzzerStateEnum::Complete)
+            }
+            _ => None,
+        };
+
+        if alert != self.last_alert {
+            self.last_alert = alert;
+            alert
+        } else {
+            None
+        }
+    }
+
+    /**
+    ### Menu update of identification runs
+    * Autotune cycle results, characterize progress and fitted model
+    * Set by `get_current_target`, caller sends it without blocking the control step
+    */
+    pub fn take_menu(&mut self) -> Option<SyncMenuStateEnum> {
+        self.menu_msg.take()
+    }
 
-        let ku = 4.0f32 * 1.0f32 / (PI * amplitude);
+    /**
+    ### Static hold subject to idle auto-off
+    * Bake runs are intentional long holds and are exempt
+    */
+    pub fn is_idle_hold(&self) -> bool {
+        matches!(self.profile, TemperatureProfileEnum::Static) && self.peak > 0
+    }
+
+    /**
+    ### Profile is in a cooldown stage
+    * Requests fan cooling
+    */
+    pub fn is_cooldown(&self) -> bool {
+        matches!(
+            self.profile,
+            TemperatureProfileEnum::ProfileA {
+                state: TemperatureProfileAState::Cooldown
+            } | TemperatureProfileEnum::AutoCalibrate {
+                state: TemperatureAutoCalibrateState::Cooldown
+            } | TemperatureProfileEnum::Characterize {
+                state: TemperatureCharacterizeState::Cooldown
+            }
+        )
+    }
+
+    /**
+    ### Profile identifies plate response
+    * Autotune relay and step response fits assume requested duty is delivered
+    */
+    pub fn is_identification(&self) -> bool {
+        matches!(
+            self.profile,
+            TemperatureProfileEnum::AutoCalibrate { .. }
+                | TemperatureProfileEnum::Characterize { .. }
+        )
+    }
 
-        let tu = time_diff;
-        let ti = 0.5f32 * tu;
-        let td = 0.125f32 * tu;
+    /**
+    ### Profile is holding plate at soak temperature
+    */
+    pub fn is_soak(&self) -> bool {
+        matches!(
+            self.profile,
+            TemperatureProfileEnum::ProfileA {
+                state: TemperatureProfileAState::PreHeat | TemperatureProfileAState::PreHeatExtra
+            }
+        )
+    }
 
-        let kp = 0.6f32 * ku;
-        let ki = kp / ti;
-        let kd = kp * td;
+    /**
+    ### Highest temperature current stage may reach
+    * Manual target is only an expected temperature, limit is `manual_temp_max`
+    * Other profiles are limited by their current target
+    */
+    pub fn temp_limit(&self) -> u16 {
+        match self.profile {
+            TemperatureProfileEnum::Manual { .. } => self.manual_temp_max,
+            _ => self.last_target,
+        }
+    }
 
-        (kp, ki, kd)
+    /**
+    ### Open-loop output requested by profile
+    * Duty 0..1, bypasses PID and bang-bang in `Heater`
+    */
+    pub fn get_output_override(&self) -> Option<f32> {
+        match &self.profile {
+            TemperatureProfileEnum::AutoCalibrate { state } => match state {
+                TemperatureAutoCalibrateState::FirstRamp
+                | TemperatureAutoCalibrateState::RelayHeating { .. } => {
+                    Some(self.autotune.amplitude())
+                }
+                TemperatureAutoCalibrateState::RelayCooling { .. }
+                | TemperatureAutoCalibrateState::Cooldown => Some(0.0),
+            },
+            TemperatureProfileEnum::Characterize { state } => match state {
+                TemperatureCharacterizeState::Step => Some(self.step_response.duty()),
+                TemperatureCharacterizeState::Cooldown => Some(0.0),
+            },
+            TemperatureProfileEnum::Manual { duty } => {
+                if self.time < self.manual_time_max {
+                    Some(*duty as f32 / 100.0)
+                } else {
+                    Some(0.0)
+                }
+            }
+            _ => None,
+        }
     }
 
-    pub fn update(&mut self, duration: Duration, curr_temp: u16, heating: bool) {
+    pub fn update(
+        &mut self,
+        duration: Duration,
+        curr_temp: f32,
+        board_temp: Option<u16>,
+        heating: bool,
+    ) {
         self.time += duration.as_millis() as f32 / 1000.0;
-        self.temperature = curr_temp;
-        self.heating = heating;
+        self.temperature = curr_temp as u16;
+        self.precise_temperature = curr_temp;
+        self.board_temperature = board_temp;
         if self.temperature > self.curr_max_temp {
             self.curr_max_temp =
                 ((self.temperature as f32 * 0.9) + (self.curr_max_temp as f32 * 0.1)) as u16;
         }
-        if !matches!(self.profile, TemperatureProfileEnum::AutoCalibrate { .. }) {
+        if matches!(self.profile, TemperatureProfileEnum::Manual { .. })
+            && self.temperature > self.manual_temp_max
+        {
+            panic!(
+                "Manual over-temp\n{:03} > {:03}!",
+                self.temperature, self.manual_temp_max
+            );
+        }
+        if !matches!(
+            self.profile,
+            TemperatureProfileEnum::AutoCalibrate { .. }
+                | TemperatureProfileEnum::Characterize { .. }
+        ) {
             self.check_thermal_runaway(duration, heating);
         }
     }
@@ -39,9 +39,8 @@ impl Thermistor {
         (c1, c2, c3)
     }
 
-    pub(crate) fn calc_temp(&self, val: u16) -> f32 {
-        let fval = val as f32;
-        let r = R * fval / (VAL_MAX - fval);
+    pub(crate) fn calc_temp(&self, val: f32) -> f32 {
+        let r = R * val / (VAL_MAX - val);
         let ln_r = (r - INLINE_R).ln();
         let inv_t = self.c1 + self.c2 * ln_r + self.c3 * ln_r.powi(3);
         1.0 / inv_t + KELVIN_TO_CELSIUS
@@ -57,3 +57,8 @@ pub(crate) type SyncStateChannelReceiver<'a, T> = embassy_sync::channel::Receive
 >;
 pub(crate) type SyncStateChannelSender<'a, T> =
     embassy_sync::channel::Sender<'a, embassy_sync::blocking_mutex::raw::ThreadModeRawMutex, T, 4>;
+
+pub(crate) type SharedAdc<'a> = embassy_sync::mutex::Mutex<
+    embassy_sync::blocking_mutex::raw::ThreadModeRawMutex,
+    embassy_rp::adc::Adc<'a, embassy_rp::adc::Async>,
+>;
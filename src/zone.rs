@@ -0,0 +1,327 @@
+use bincode::{Decode, Encode};
+use embassy_rp::pwm::{self, Pwm};
+use fixed::traits::ToFixed;
+
+use crate::output::{Output, OutputModeEnum};
+use crate::pid::Controller;
+use crate::source::{TemperatureReading, TemperatureSource, TemperatureSourceFault};
+use crate::storage;
+
+/**
+### Number of heater zones
+* Zone 0 is main plate, controlled by `Heater` profile, PID and cascade
+* Zones 1.. are auxiliary, each with own settings in `StorageData::zones`, off until enabled
+* Pins are wired in `main` and `reset_peripherals_on_exception`, their arrays are sized by `ZONES`
+* Zone 0: plate sensor, output PIN_22 on PWM_SLICE3
+* Zone 1: thermistor on PIN_28, output PIN_14 on PWM_SLICE7
+* Free ADC inputs end at PIN_28, more zones need an external ADC
+*/
+pub(crate) const ZONES: usize = 2;
+pub(crate) const AUX_ZONES: usize = ZONES - 1;
+
+const ZONE_NOISE_FAULT_MAX: f32 = 5.0;
+const ZONE_NOISE_FAULT_COUNT: u8 = 20;
+const ZONE_RUNAWAY_BELOW: f32 = 5.0;
+const ZONE_RUNAWAY_DEMAND: f32 = 0.5;
+const ZONE_RUNAWAY_TIME: f32 = 30.0;
+const ZONE_RUNAWAY_RISE: f32 = 2.0;
+const ZONE_OVER_TEMP: f32 = 30.0;
+const ZONE_TEMP_MAX: f32 = 330.0; //above any run plus margin, used until a limit holds
+
+#[derive(Debug, Clone, Copy, PartialEq, Encode, Decode)]
+pub(crate) struct ZoneSettings {
+    pub enabled: bool,
+    pub offset: i16,
+    pub pid_p: f32,
+    pub pid_i: f32,
+    pub pid_d: f32,
+}
+
+impl Default for ZoneSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            offset: 0,
+            pid_p: 0.05,
+            pid_i: 0.002,
+            pid_d: 0.0,
+        }
+    }
+}
+
+/**
+### PWM setup shared by all zone slices
+* Slices with equal top and divider keep their counter offset, see `Zone::set_phase`
+*/
+pub(crate) fn pwm_config() -> pwm::Config {
+    let mut pwm_config = pwm::Config::default();
+    pwm_config.divider = 16.to_fixed();
+    pwm_config.compare_a = 0;
+    pwm_config
+}
+
+/**
+### Heater zone
+* Own sensor and output, sensor and noise checks for every zone
+* Main zone demand comes from `Heater`, its runaway and over-temp checks follow the profile in `TemperatureProfile`
+* Auxiliary zones have own PID gains, follow main profile target plus `offset`
+* Open-loop overrides of main zone (autotune, characterize, manual) drive auxiliary zones with same duty
+* Noise: `ZONE_NOISE_FAULT_COUNT` reads in a row above `ZONE_NOISE_FAULT_MAX`
+* Runaway (auxiliary): zone must rise `ZONE_RUNAWAY_RISE` within `ZONE_RUNAWAY_TIME` while heating hard
+* Over-temp (auxiliary): zone must stay within `ZONE_OVER_TEMP` of its temperature limit
+* Lowered limit applies once zone has cooled below it, a lowered setpoint doesn't trip a hot zone
+* Checks run on closed-loop and override paths
+* Disabled zones are never read and stay off
+*/
+pub(crate) struct Zone<'a, S> {
+    number: u8,
+    settings: ZoneSettings,
+    source: S,
+    pwm: Pwm<'a>,
+    pwm_config: pwm::Config,
+    output: Output,
+    controller: Controller,
+    reading: Option<TemperatureReading>,
+    noise_faults: u8,
+    watch_time: f32,
+    watch_temp: f32,
+    over_temp_limit: f32,
+}
+
+impl<'a, S: TemperatureSource> Zone<'a, S> {
+    /**
+    ### New zone
+    * `index` 0 is main zone, always enabled, auxiliary zone settings are `StorageData::zones[index - 1]`
+    * Displayed as zone `index + 1`
+    * `pwm` must be set up by `pwm_config`
+    */
+    pub fn new(
+        index: usize,
+        source: S,
+        pwm: Pwm<'a>,
+        startup_storage: &storage::StorageData,
+    ) -> Self {
+        let settings = match index.checked_sub(1) {
+            Some(aux) => startup_storage.zones[aux],
+            None => ZoneSettings {
+                enabled: true,
+                ..Default::default()
+            },
+        };
+
+        let mut controller = Controller::new(0.0, settings.pid_p, settings.pid_i, settings.pid_d);
+        controller.set_integral_limits(Some(0.0), Some(1.0));
+
+        let mut this = Self {
+            number: index as u8 + 1,
+            settings,
+            source,
+            pwm,
+            pwm_config: pwm_config(),
+            output: Output::new(
+                startup_storage.output_mode,
+                startup_storage.output_window,
+                startup_storage.output_min_on,
+                startup_storage.output_min_off,
+            ),
+            controller,
+            reading: None,
+            noise_faults: 0,
+            watch_time: 0.0,
+            watch_temp: 0.0,
+            over_temp_limit: ZONE_TEMP_MAX,
+        };
+        //normally already applied by `Pwm::new_output_a`, keeps counter rate equal to other zones
+        this.pwm.set_config(&this.pwm_config);
+
+        this
+    }
+
+    pub fn set_settings(&mut self, settings: ZoneSettings) {
+        self.settings = settings;
+        self.controller.set_proportional_gain(settings.pid_p);
+        self.controller.set_integral_gain(settings.pid_i);
+        self.controller.set_derivative_gain(settings.pid_d);
+        self.reset();
+        if !settings.enabled {
+            self.reading = None;
+            self.set_output(0.0);
+        }
+    }
+
+    pub fn set_output_settings(
+        &mut self,
+        mode: OutputModeEnum,
+        window: f32,
+        min_on: f32,
+        min_off: f32,
+    ) {
+        self.output.set_settings(mode, window, min_on, min_off);
+    }
+
+    /**
+    ### Shifts zone output period
+    * `phase` 0..1 of PWM period and SSR window, relative to main zone
+    * Call once all zone slices run with `pwm_config`, before any output is set
+    * Slices then count at the same rate and `set_output` only changes compare, so counter offset holds
+    */
+    pub fn set_phase(&mut self, phase: f32) {
+        self.output.set_phase(phase);
+        self.pwm
+            .set_counter((phase * (self.pwm_config.top as f32 + 1.0)) as u16);
+    }
+
+    pub fn reset(&mut self) {
+        self.controller.reset();
+        self.output.reset();
+        self.watch_time = 0.0;
+    }
+
+    pub fn number(&self) -> u8 {
+        self.number
+    }
+
+    pub fn temp(&self) -> Option<u16> {
+        self.reading.map(|x| x.temp as u16)
+    }
+
+    pub fn output_enabled(&self) -> bool {
+        self.pwm_config.compare_a > 0
+    }
+
+    /**
+    ### Reads zone sensor without checks
+    * For POST, which reports its own faults
+    */
+    pub async fn read_source(&mut self) -> Result<TemperatureReading, TemperatureSourceFault> {
+        self.source.read().await
+    }
+
+    /**
+    ### Reads zone sensor and runs its checks
+    * Sensor faults and noise panic
+    */
+    pub async fn read(&mut self) -> Option<TemperatureReading> {
+        if !self.settings.enabled {
+            return None;
+        }
+
+        let reading = match self.source.read().await {
+            Ok(x) => x,
+            Err(fault) => panic!("Zone {} sensor fault\n{:?}!", self.number, fault),
+        };
+        if reading.noise > ZONE_NOISE_FAULT_MAX {
+            self.noise_faults += 1;
+            if self.noise_faults >= ZONE_NOISE_FAULT_COUNT {
+                panic!("Zone {} noise\nstd: {:.1}!", self.number, reading.noise);
+            }
+        } else {
+            self.noise_faults = 0;
+        }
+        self.reading = Some(reading);
+
+        Some(reading)
+    }
+
+    /**
+    ### Auxiliary zone demand for this step
+    * `target`: main profile target, 0 is off
+    * `temp_limit`: highest main temperature of current stage, see `TemperatureProfile::temp_limit`
+    * `duty_override`: open-loop duty of main zone
+    */
+    pub fn demand(
+        &mut self,
+        target: u16,
+        temp_limit: u16,
+        duty_override: Option<f32>,
+        dt: f32,
+    ) -> f32 {
+        let Some(reading) = self.reading else {
+            return 0.0;
+        };
+        if !self.settings.enabled {
+            return 0.0;
+        }
+
+        let zone_target = (target as i16 + self.settings.offset).max(0) as f32;
+        let duty = if target == 0 {
+            self.controller.reset();
+            0.0
+        } else if let Some(duty) = duty_override {
+            duty.clamp(0.0, 1.0)
+        } else {
+            self.controller.set_target(zone_target);
+            self.controller
+                .update_elapsed(reading.temp, core::time::Duration::from_secs_f32(dt))
+                .clamp(0.0, 1.0)
+        };
+
+        self.check(target, temp_limit, duty, dt);
+
+        duty
+    }
+
+    /**
+    ### Runs over-temp and runaway checks
+    * `target`, `temp_limit`: main profile values, zone `offset` is added
+    * `duty`: zone demand of this step
+    */
+    fn check(&mut self, target: u16, temp_limit: u16, duty: f32, dt: f32) {
+        let Some(reading) = self.reading else {
+            return;
+        };
+
+        let zone_target = (target as i16 + self.settings.offset).max(0) as f32;
+        self.check_over_temp(temp_limit.max(target), reading.temp);
+        self.check_runaway(zone_target, reading.temp, duty, dt);
+    }
+
+    /**
+    ### Sets zone output
+    * `demand` is final duty after power limits and allocation
+    */
+    pub fn set_output(&mut self, demand: f32) {
+        self.pwm_config.compare_a = self.output.compare(demand, self.pwm_config.top);
+        self.pwm.set_config(&self.pwm_config);
+    }
+
+    fn check_over_temp(&mut self, temp_limit: u16, temp: f32) {
+        let zone_limit = (temp_limit as i16 + self.settings.offset).max(0) as f32 + ZONE_OVER_TEMP;
+        if temp <= zone_limit {
+            self.over_temp_limit = zone_limit;
+        } else {
+            //still above new limit, keep previous one until cooled down
+            self.over_temp_limit = self.over_temp_limit.max(zone_limit);
+        }
+
+        if temp > self.over_temp_limit {
+            panic!(
+                "Zone {} over-temp\n{:.0} > {:.0}!",
+                self.number, temp, self.over_temp_limit
+            );
+        }
+    }
+
+    fn check_runaway(&mut self, target: f32, temp: f32, duty: f32, dt: f32) {
+        if duty < ZONE_RUNAWAY_DEMAND || temp + ZONE_RUNAWAY_BELOW >= target {
+            self.watch_time = 0.0;
+            return;
+        }
+
+        if self.watch_time == 0.0 {
+            self.watch_temp = temp;
+        }
+        self.watch_time += dt;
+        if self.watch_time >= ZONE_RUNAWAY_TIME {
+            if temp < self.watch_temp + ZONE_RUNAWAY_RISE {
+                panic!(
+                    "Zone {} runaway\n{:.0} < {:.0}!",
+                    self.number,
+                    temp,
+                    self.watch_temp + ZONE_RUNAWAY_RISE
+                );
+            }
+            self.watch_time = 0.0;
+        }
+    }
+}